@@ -0,0 +1,29 @@
+-- The results in fixtures/results.json, for seeding a local D1 database.
+INSERT INTO results_rust (date, time, username, rank) VALUES
+    ('2026-10-05', 38, 'carol', 1),
+    ('2026-10-05', 42, 'alice', 2),
+    ('2026-10-05', 65, 'bob', 3),
+    ('2026-10-05', 90, 'dave', 4),
+    ('2026-10-06', 45, 'carol', 1),
+    ('2026-10-06', 51, 'alice', 2),
+    ('2026-10-06', 58, 'bob', 3),
+    ('2026-10-07', 37, 'alice', 1),
+    ('2026-10-07', 41, 'carol', 2),
+    ('2026-10-07', 70, 'bob', 3),
+    ('2026-10-07', 84, 'dave', 4),
+    ('2026-10-08', 39, 'carol', 1),
+    ('2026-10-08', 44, 'alice', 2),
+    ('2026-10-08', 44, 'bob', 2),
+    ('2026-10-09', 52, 'carol', 1),
+    ('2026-10-09', 60, 'alice', 2),
+    ('2026-10-09', 75, 'dave', 3),
+    ('2026-10-10', 102, 'carol', 1),
+    ('2026-10-10', 118, 'alice', 2),
+    ('2026-10-10', 140, 'bob', 3),
+    ('2026-10-10', 201, 'dave', 4),
+    ('2026-10-11', 47, 'carol', 1),
+    ('2026-10-11', 61, 'bob', 2),
+    ('2026-10-12', 35, 'alice', 1),
+    ('2026-10-12', 40, 'carol', 2),
+    ('2026-10-12', 49, 'bob', 3),
+    ('2026-10-12', 66, 'dave', 4);
@@ -0,0 +1,55 @@
+-- Tables used when STORAGE_BACKEND is "d1". They mirror the Supabase tables of the same name.
+
+CREATE TABLE results_rust (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    date TEXT NOT NULL,
+    time INTEGER NOT NULL,
+    username TEXT NOT NULL,
+    rank INTEGER NOT NULL,
+    UNIQUE (date, username)
+);
+CREATE INDEX results_rust_username ON results_rust (username);
+CREATE INDEX results_rust_time ON results_rust (time);
+
+CREATE TABLE all_rust (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    username TEXT NOT NULL,
+    mu REAL NOT NULL,
+    sigma REAL NOT NULL,
+    average_time REAL NOT NULL,
+    num_wins INTEGER NOT NULL,
+    num_played INTEGER NOT NULL,
+    elo REAL NOT NULL
+);
+
+CREATE TABLE last_30_rust (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    username TEXT NOT NULL,
+    mu REAL NOT NULL,
+    sigma REAL NOT NULL,
+    average_time REAL NOT NULL,
+    num_wins INTEGER NOT NULL,
+    num_played INTEGER NOT NULL,
+    elo REAL NOT NULL
+);
+
+CREATE TABLE last_90_rust (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    username TEXT NOT NULL,
+    mu REAL NOT NULL,
+    sigma REAL NOT NULL,
+    average_time REAL NOT NULL,
+    num_wins INTEGER NOT NULL,
+    num_played INTEGER NOT NULL,
+    elo REAL NOT NULL
+);
+
+CREATE TABLE rating_history_rust (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    date TEXT NOT NULL,
+    username TEXT NOT NULL,
+    mu REAL NOT NULL,
+    sigma REAL NOT NULL,
+    elo REAL NOT NULL
+);
+CREATE INDEX rating_history_rust_username_date ON rating_history_rust (username, date);
@@ -0,0 +1,15 @@
+-- Final standings of completed seasons, written by the rebuild. Mirrors the Supabase table of the same name.
+
+CREATE TABLE season_standings_rust (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    season_id TEXT NOT NULL,
+    rank INTEGER NOT NULL,
+    username TEXT NOT NULL,
+    mu REAL NOT NULL,
+    sigma REAL NOT NULL,
+    average_time REAL NOT NULL,
+    num_wins INTEGER NOT NULL,
+    num_played INTEGER NOT NULL,
+    elo REAL NOT NULL
+);
+CREATE INDEX season_standings_rust_season_id_rank ON season_standings_rust (season_id, rank);
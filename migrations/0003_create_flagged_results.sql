@@ -0,0 +1,13 @@
+-- Results the rebuild flagged as suspicious, and whether an admin has approved or rejected them. Mirrors the
+-- Supabase table of the same name.
+
+CREATE TABLE flagged_results_rust (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    date TEXT NOT NULL,
+    username TEXT NOT NULL,
+    time INTEGER NOT NULL,
+    reason TEXT NOT NULL,
+    detail TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+);
+CREATE INDEX flagged_results_rust_date_username ON flagged_results_rust (date, username);
@@ -0,0 +1,10 @@
+-- Every change an admin made to the stored results. Mirrors the Supabase table of the same name.
+
+CREATE TABLE audit_log_rust (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp TEXT NOT NULL,
+    action TEXT NOT NULL,
+    description TEXT NOT NULL,
+    note TEXT NOT NULL DEFAULT ''
+);
+CREATE INDEX audit_log_rust_timestamp ON audit_log_rust (timestamp);
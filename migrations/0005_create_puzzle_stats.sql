@@ -0,0 +1,24 @@
+-- The difficulty of every puzzle and the scores of every result, recomputed by each rebuild. Mirrors the Supabase
+-- tables of the same name.
+
+CREATE TABLE puzzle_difficulties_rust (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    date TEXT NOT NULL,
+    difficulty REAL NOT NULL,
+    label TEXT NOT NULL,
+    median_time REAL NOT NULL,
+    num_solvers INTEGER NOT NULL
+);
+CREATE INDEX puzzle_difficulties_rust_date ON puzzle_difficulties_rust (date);
+
+CREATE TABLE result_scores_rust (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    date TEXT NOT NULL,
+    username TEXT NOT NULL,
+    time INTEGER NOT NULL,
+    rank INTEGER NOT NULL,
+    field_score REAL,
+    personal_score REAL
+);
+CREATE INDEX result_scores_rust_date ON result_scores_rust (date);
+CREATE INDEX result_scores_rust_username ON result_scores_rust (username);
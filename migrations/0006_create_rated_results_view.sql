@@ -0,0 +1,13 @@
+-- Every result except the flagged ones that haven't been approved, which is what the podium ranks. Mirrors the
+-- Supabase view of the same name.
+
+CREATE VIEW rated_results_rust AS
+SELECT results_rust.*
+FROM results_rust
+WHERE NOT EXISTS (
+    SELECT 1
+    FROM flagged_results_rust
+    WHERE flagged_results_rust.date = results_rust.date
+        AND flagged_results_rust.username = results_rust.username
+        AND flagged_results_rust.status != 'approved'
+);
@@ -0,0 +1,686 @@
+use chrono::{NaiveDate, Utc};
+use worker::d1::{D1Database, D1PreparedStatement};
+use worker::query;
+
+use crate::models::{
+    AuditEntry, FlaggedResult, LeaderboardEntry, ModerationStatus, PodiumFilter, PuzzleDifficulty,
+    RatingSnapshot, ResultEntry, ResultScore, SeasonStanding, UserData,
+};
+use crate::store::{ResultStore, StoreError};
+use crate::util::{build_user_data, merge_new_results, rerank_results};
+
+/// The leaderboard tables created by `migrations/0001_create_tables.sql`.
+const LEADERBOARD_TABLES: [&str; 3] = ["all_rust", "last_30_rust", "last_90_rust"];
+
+/// The tables keyed by username that move with a user's results when users are merged.
+const USER_TABLES: [&str; 4] = [
+    "results_rust",
+    "flagged_results_rust",
+    "rating_history_rust",
+    "result_scores_rust",
+];
+
+/// The maximum number of statements sent to D1 in a single batch.
+const BATCH_SIZE: usize = 500;
+
+const RESULT_COLUMNS: &str = "date, time, username, rank";
+
+const LEADERBOARD_COLUMNS: &str = "username, mu, sigma, average_time, num_wins, num_played, elo";
+
+const RATING_SNAPSHOT_COLUMNS: &str = "date, username, mu, sigma, elo";
+
+const SEASON_STANDING_COLUMNS: &str =
+    "season_id, rank, username, mu, sigma, average_time, num_wins, num_played, elo";
+
+const PUZZLE_DIFFICULTY_COLUMNS: &str = "date, difficulty, label, median_time, num_solvers";
+
+const RESULT_SCORE_COLUMNS: &str = "date, username, time, rank, field_score, personal_score";
+
+const FLAGGED_RESULT_COLUMNS: &str = "date, username, time, reason, detail, status";
+
+const AUDIT_ENTRY_COLUMNS: &str = "timestamp, action, description, note";
+
+/// A `ResultStore` backed by a Cloudflare D1 database.
+pub struct D1Store {
+    db: D1Database,
+}
+
+impl D1Store {
+    pub fn new(db: D1Database) -> Self {
+        Self { db }
+    }
+
+    /// Returns `db_name` if it's one of the leaderboard tables, since table names can't be bound as parameters.
+    fn leaderboard_table(db_name: &str) -> Result<&'static str, StoreError> {
+        LEADERBOARD_TABLES
+            .into_iter()
+            .find(|table| *table == db_name)
+            .ok_or_else(|| StoreError::UnknownLeaderboard(db_name.to_string()))
+    }
+
+    /// Builds the statements writing the ranks of results whose rank changed.
+    fn rank_updates(
+        &self,
+        entries: &[ResultEntry],
+    ) -> Result<Vec<D1PreparedStatement>, StoreError> {
+        let mut statements = Vec::new();
+        for entry in entries {
+            statements.push(query!(
+                &self.db,
+                "UPDATE results_rust SET rank = ?1 WHERE date = ?2 AND username = ?3",
+                entry.rank,
+                entry.date,
+                entry.username
+            )?);
+        }
+
+        Ok(statements)
+    }
+
+    /// Runs `statements` as a single batch, which D1 applies atomically.
+    async fn run_batch(&self, statements: Vec<D1PreparedStatement>) -> Result<(), StoreError> {
+        for result in self.db.batch(statements).await? {
+            if let Some(error) = result.error() {
+                return Err(StoreError::Batch(error));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs `statements` in batches of `BATCH_SIZE`. Each batch is applied atomically, but if a later batch
+    /// fails, the earlier ones stay applied.
+    async fn run_batched(&self, statements: Vec<D1PreparedStatement>) -> Result<(), StoreError> {
+        for chunk in statements.chunks(BATCH_SIZE) {
+            self.run_batch(chunk.to_vec()).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Replaces every row of `table` with `rows`, so readers never see it half written.
+    ///
+    /// There can be more rows than fit in one batch, so they're written to a staging table first and then swapped
+    /// in with a single batch, which D1 applies atomically. If anything fails, `table` keeps its old rows.
+    ///
+    /// `insert` builds the statement inserting a row into the table it's given.
+    async fn replace_table<T>(
+        &self,
+        table: &str,
+        columns: &str,
+        rows: &[T],
+        insert: impl Fn(&str, &T) -> worker::Result<D1PreparedStatement>,
+    ) -> Result<(), StoreError> {
+        let staging = format!("{table}_staging_{}", Utc::now().timestamp_micros());
+        let statements = rows
+            .iter()
+            .map(|row| insert(&staging, row))
+            .collect::<worker::Result<Vec<_>>>()?;
+
+        query!(
+            &self.db,
+            &format!("CREATE TABLE {staging} AS SELECT {columns} FROM {table} WHERE 0")
+        )
+        .run()
+        .await?;
+        if let Err(error) = self.run_batched(statements).await {
+            // The copy error is the one worth reporting, so a failure to clean up is ignored.
+            let _ = query!(&self.db, &format!("DROP TABLE {staging}"))
+                .run()
+                .await;
+            return Err(error);
+        }
+
+        self.run_batch(vec![
+            query!(&self.db, &format!("DELETE FROM {table}")),
+            query!(
+                &self.db,
+                &format!("INSERT INTO {table} ({columns}) SELECT {columns} FROM {staging}")
+            ),
+            query!(&self.db, &format!("DROP TABLE {staging}")),
+        ])
+        .await
+    }
+}
+
+impl ResultStore for D1Store {
+    async fn fetch_results(&self, date: &str) -> Result<Vec<ResultEntry>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!("SELECT {RESULT_COLUMNS} FROM results_rust WHERE date = ?1 ORDER BY time"),
+            date
+        )?;
+
+        Ok(statement.all().await?.results()?)
+    }
+
+    async fn fetch_all_results(&self) -> Result<Vec<ResultEntry>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!("SELECT {RESULT_COLUMNS} FROM results_rust ORDER BY date, time, username")
+        );
+
+        Ok(statement.all().await?.results()?)
+    }
+
+    async fn fetch_results_since(&self, date: &str) -> Result<Vec<ResultEntry>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!(
+                "SELECT {RESULT_COLUMNS} FROM results_rust WHERE date >= ?1 ORDER BY date, time, username"
+            ),
+            date
+        )?;
+
+        Ok(statement.all().await?.results()?)
+    }
+
+    async fn insert_results(
+        &self,
+        date: &str,
+        new_entries: Vec<ResultEntry>,
+    ) -> Result<Vec<ResultEntry>, StoreError> {
+        let existing_entries = self.fetch_results(date).await?;
+        let (inserted_entries, reranked_entries) =
+            merge_new_results(date, &existing_entries, new_entries)?;
+
+        let mut statements = Vec::new();
+        for entry in &inserted_entries {
+            statements.push(query!(
+                &self.db,
+                &format!("INSERT INTO results_rust ({RESULT_COLUMNS}) VALUES (?1, ?2, ?3, ?4)"),
+                entry.date,
+                entry.time,
+                entry.username,
+                entry.rank
+            )?);
+        }
+        statements.extend(self.rank_updates(&reranked_entries)?);
+        self.run_batched(statements).await?;
+
+        Ok(inserted_entries)
+    }
+
+    async fn update_result_time(
+        &self,
+        date: &str,
+        username: &str,
+        time: i32,
+    ) -> Result<(), StoreError> {
+        query!(
+            &self.db,
+            "UPDATE results_rust SET time = ?1 WHERE date = ?2 AND username = ?3",
+            time,
+            date,
+            username
+        )?
+        .run()
+        .await?;
+
+        let entries = self.fetch_results(date).await?;
+        self.run_batched(self.rank_updates(&rerank_results(entries))?)
+            .await
+    }
+
+    async fn delete_result(&self, date: &str, username: &str) -> Result<(), StoreError> {
+        query!(
+            &self.db,
+            "DELETE FROM results_rust WHERE date = ?1 AND username = ?2",
+            date,
+            username
+        )?
+        .run()
+        .await?;
+
+        let entries = self.fetch_results(date).await?;
+        self.run_batched(self.rank_updates(&rerank_results(entries))?)
+            .await
+    }
+
+    async fn rename_user(&self, from: &str, to: &str) -> Result<(), StoreError> {
+        let mut statements = Vec::new();
+        for table in USER_TABLES {
+            statements.push(query!(
+                &self.db,
+                &format!("UPDATE {table} SET username = ?1 WHERE username = ?2"),
+                to,
+                from
+            )?);
+        }
+
+        // One batch, so D1 applies every update or none of them.
+        self.run_batch(statements).await
+    }
+
+    async fn fetch_audit_log(&self) -> Result<Vec<AuditEntry>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!(
+                "SELECT {AUDIT_ENTRY_COLUMNS} FROM audit_log_rust ORDER BY timestamp DESC, id DESC"
+            )
+        );
+
+        Ok(statement.all().await?.results()?)
+    }
+
+    async fn insert_audit_entry(&self, entry: &AuditEntry) -> Result<(), StoreError> {
+        query!(
+            &self.db,
+            &format!("INSERT INTO audit_log_rust ({AUDIT_ENTRY_COLUMNS}) VALUES (?1, ?2, ?3, ?4)"),
+            entry.timestamp,
+            entry.action,
+            entry.description,
+            entry.note
+        )?
+        .run()
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_most_recent_crossword_date(&self) -> Result<NaiveDate, StoreError> {
+        let date: Option<String> = query!(&self.db, "SELECT MAX(date) AS date FROM results_rust")
+            .first(Some("date"))
+            .await?;
+
+        Ok(NaiveDate::parse_from_str(
+            &date.ok_or(StoreError::NoResults)?,
+            "%Y-%m-%d",
+        )?)
+    }
+
+    async fn fetch_usernames_sorted_by_elo(&self) -> Result<Vec<String>, StoreError> {
+        Ok(self
+            .fetch_leaderboard_from_db("all_rust")
+            .await?
+            .into_iter()
+            .map(|entry| entry.username)
+            .collect())
+    }
+
+    async fn fetch_podium_data(
+        &self,
+        filter: &PodiumFilter,
+    ) -> Result<Vec<ResultEntry>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!(
+                "SELECT {RESULT_COLUMNS} FROM rated_results_rust
+                WHERE (?1 IS NULL OR CAST(strftime('%w', date) AS INTEGER) = ?1)
+                    AND (?2 IS NULL OR CAST(strftime('%m', date) AS INTEGER) = ?2)
+                    AND (?3 IS NULL OR CAST(strftime('%Y', date) AS INTEGER) = ?3)
+                ORDER BY time, date
+                LIMIT ?4"
+            ),
+            filter.weekday.map(|weekday| weekday.num_days_from_sunday()),
+            filter.month,
+            filter.year,
+            filter.limit
+        )?;
+
+        Ok(statement.all().await?.results()?)
+    }
+
+    async fn fetch_user_data(&self, username: &str) -> Result<UserData, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!("SELECT {RESULT_COLUMNS} FROM results_rust WHERE username = ?1 ORDER BY time"),
+            username
+        )?;
+
+        Ok(build_user_data(statement.all().await?.results()?))
+    }
+
+    async fn fetch_leaderboard_from_db(
+        &self,
+        db_name: &str,
+    ) -> Result<Vec<LeaderboardEntry>, StoreError> {
+        let table = Self::leaderboard_table(db_name)?;
+        let statement = query!(
+            &self.db,
+            &format!("SELECT {LEADERBOARD_COLUMNS} FROM {table} ORDER BY elo DESC")
+        );
+
+        Ok(statement.all().await?.results()?)
+    }
+
+    async fn replace_leaderboard_in_db(
+        &self,
+        db_name: &str,
+        entries: &[LeaderboardEntry],
+    ) -> Result<(), StoreError> {
+        let table = Self::leaderboard_table(db_name)?;
+
+        self.replace_table(table, LEADERBOARD_COLUMNS, entries, |table, entry| {
+            query!(
+                &self.db,
+                &format!(
+                    "INSERT INTO {table} ({LEADERBOARD_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+                ),
+                entry.username,
+                entry.mu,
+                entry.sigma,
+                entry.average_time,
+                entry.num_wins,
+                entry.num_played,
+                entry.elo
+            )
+        })
+        .await
+    }
+
+    async fn fetch_rating_history(
+        &self,
+        username: &str,
+    ) -> Result<Vec<RatingSnapshot>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!(
+                "SELECT {RATING_SNAPSHOT_COLUMNS} FROM rating_history_rust WHERE username = ?1 ORDER BY date"
+            ),
+            username
+        )?;
+
+        Ok(statement.all().await?.results()?)
+    }
+
+    async fn replace_rating_history_in_db(
+        &self,
+        history: &[RatingSnapshot],
+    ) -> Result<(), StoreError> {
+        self.replace_table(
+            "rating_history_rust",
+            RATING_SNAPSHOT_COLUMNS,
+            history,
+            |table, snapshot| {
+                query!(
+                    &self.db,
+                    &format!(
+                        "INSERT INTO {table} ({RATING_SNAPSHOT_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5)"
+                    ),
+                    snapshot.date,
+                    snapshot.username,
+                    snapshot.mu,
+                    snapshot.sigma,
+                    snapshot.elo
+                )
+            },
+        )
+        .await
+    }
+
+    async fn fetch_season_standings(
+        &self,
+        season_id: &str,
+    ) -> Result<Vec<SeasonStanding>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!(
+                "SELECT {SEASON_STANDING_COLUMNS} FROM season_standings_rust WHERE season_id = ?1 ORDER BY rank"
+            ),
+            season_id
+        )?;
+
+        Ok(statement.all().await?.results()?)
+    }
+
+    async fn fetch_season_podiums(&self) -> Result<Vec<SeasonStanding>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!(
+                "SELECT {SEASON_STANDING_COLUMNS} FROM season_standings_rust WHERE rank <= 3 ORDER BY season_id, rank"
+            )
+        );
+
+        Ok(statement.all().await?.results()?)
+    }
+
+    async fn replace_season_standings_in_db(
+        &self,
+        standings: &[SeasonStanding],
+    ) -> Result<(), StoreError> {
+        self.replace_table(
+            "season_standings_rust",
+            SEASON_STANDING_COLUMNS,
+            standings,
+            |table, standing| {
+                query!(
+                    &self.db,
+                    &format!(
+                        "INSERT INTO {table} ({SEASON_STANDING_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"
+                    ),
+                    standing.season_id,
+                    standing.rank,
+                    standing.username,
+                    standing.mu,
+                    standing.sigma,
+                    standing.average_time,
+                    standing.num_wins,
+                    standing.num_played,
+                    standing.elo
+                )
+            },
+        )
+        .await
+    }
+
+    async fn fetch_puzzle_difficulties(&self) -> Result<Vec<PuzzleDifficulty>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!(
+                "SELECT {PUZZLE_DIFFICULTY_COLUMNS} FROM puzzle_difficulties_rust ORDER BY difficulty DESC, date"
+            )
+        );
+
+        Ok(statement.all().await?.results()?)
+    }
+
+    async fn fetch_puzzle_difficulty(
+        &self,
+        date: &str,
+    ) -> Result<Option<PuzzleDifficulty>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!(
+                "SELECT {PUZZLE_DIFFICULTY_COLUMNS} FROM puzzle_difficulties_rust WHERE date = ?1"
+            ),
+            date
+        )?;
+
+        Ok(statement.first(None).await?)
+    }
+
+    async fn fetch_puzzle_difficulties_since(
+        &self,
+        date: &str,
+    ) -> Result<Vec<PuzzleDifficulty>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!(
+                "SELECT {PUZZLE_DIFFICULTY_COLUMNS} FROM puzzle_difficulties_rust WHERE date >= ?1 ORDER BY date"
+            ),
+            date
+        )?;
+
+        Ok(statement.all().await?.results()?)
+    }
+
+    async fn replace_puzzle_difficulties_in_db(
+        &self,
+        difficulties: &[PuzzleDifficulty],
+    ) -> Result<(), StoreError> {
+        self.replace_table(
+            "puzzle_difficulties_rust",
+            PUZZLE_DIFFICULTY_COLUMNS,
+            difficulties,
+            |table, difficulty| {
+                query!(
+                    &self.db,
+                    &format!(
+                        "INSERT INTO {table} ({PUZZLE_DIFFICULTY_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5)"
+                    ),
+                    difficulty.date,
+                    difficulty.difficulty,
+                    difficulty.label,
+                    difficulty.median_time,
+                    difficulty.num_solvers
+                )
+            },
+        )
+        .await
+    }
+
+    async fn fetch_result_scores(&self, date: &str) -> Result<Vec<ResultScore>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!(
+                "SELECT {RESULT_SCORE_COLUMNS} FROM result_scores_rust WHERE date = ?1 ORDER BY username"
+            ),
+            date
+        )?;
+
+        Ok(statement.all().await?.results()?)
+    }
+
+    async fn fetch_best_result_scores(
+        &self,
+        username: &str,
+        limit: usize,
+    ) -> Result<Vec<ResultScore>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!(
+                "SELECT {RESULT_SCORE_COLUMNS} FROM result_scores_rust WHERE username = ?1 AND field_score IS NOT NULL ORDER BY field_score DESC LIMIT ?2"
+            ),
+            username,
+            limit
+        )?;
+
+        Ok(statement.all().await?.results()?)
+    }
+
+    async fn fetch_relative_podium_data(
+        &self,
+        filter: &PodiumFilter,
+    ) -> Result<Vec<ResultScore>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!(
+                "SELECT {RESULT_SCORE_COLUMNS} FROM result_scores_rust
+                WHERE field_score IS NOT NULL
+                    AND (?1 IS NULL OR CAST(strftime('%w', date) AS INTEGER) = ?1)
+                    AND (?2 IS NULL OR CAST(strftime('%m', date) AS INTEGER) = ?2)
+                    AND (?3 IS NULL OR CAST(strftime('%Y', date) AS INTEGER) = ?3)
+                ORDER BY field_score DESC, date
+                LIMIT ?4"
+            ),
+            filter.weekday.map(|weekday| weekday.num_days_from_sunday()),
+            filter.month,
+            filter.year,
+            filter.limit
+        )?;
+
+        Ok(statement.all().await?.results()?)
+    }
+
+    async fn replace_result_scores_in_db(&self, scores: &[ResultScore]) -> Result<(), StoreError> {
+        self.replace_table(
+            "result_scores_rust",
+            RESULT_SCORE_COLUMNS,
+            scores,
+            |table, score| {
+                query!(
+                    &self.db,
+                    &format!(
+                        "INSERT INTO {table} ({RESULT_SCORE_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+                    ),
+                    score.date,
+                    score.username,
+                    score.time,
+                    score.rank,
+                    score.field_score,
+                    score.personal_score
+                )
+            },
+        )
+        .await
+    }
+
+    async fn fetch_flagged_results(&self) -> Result<Vec<FlaggedResult>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!(
+                "SELECT {FLAGGED_RESULT_COLUMNS} FROM flagged_results_rust ORDER BY date DESC, username"
+            )
+        );
+
+        Ok(statement.all().await?.results()?)
+    }
+
+    async fn insert_flagged_results(&self, flags: &[FlaggedResult]) -> Result<(), StoreError> {
+        let mut statements = Vec::new();
+        for flag in flags {
+            statements.push(query!(
+                &self.db,
+                &format!(
+                    "INSERT INTO flagged_results_rust ({FLAGGED_RESULT_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+                ),
+                flag.date,
+                flag.username,
+                flag.time,
+                flag.reason,
+                flag.detail,
+                flag.status
+            )?);
+        }
+
+        self.run_batched(statements).await
+    }
+
+    async fn update_flag_status(
+        &self,
+        date: &str,
+        username: &str,
+        status: ModerationStatus,
+    ) -> Result<(), StoreError> {
+        query!(
+            &self.db,
+            "UPDATE flagged_results_rust SET status = ?1 WHERE date = ?2 AND username = ?3",
+            status,
+            date,
+            username
+        )?
+        .run()
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_flagged_result(&self, date: &str, username: &str) -> Result<(), StoreError> {
+        query!(
+            &self.db,
+            "DELETE FROM flagged_results_rust WHERE date = ?1 AND username = ?2",
+            date,
+            username
+        )?
+        .run()
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_user_trueskill_from_db(
+        &self,
+        username: &str,
+    ) -> Result<Option<(f64, f64)>, StoreError> {
+        let statement = query!(
+            &self.db,
+            &format!("SELECT {LEADERBOARD_COLUMNS} FROM all_rust WHERE username = ?1"),
+            username
+        )?;
+
+        let user_data: Option<LeaderboardEntry> = statement.first(None).await?;
+
+        Ok(user_data.map(|user_data| (user_data.mu, user_data.sigma)))
+    }
+}
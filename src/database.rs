@@ -1,12 +1,16 @@
-use chrono::{Datelike, NaiveDate, Weekday};
-use postgrest::Postgrest;
+use chrono::NaiveDate;
+use postgrest::{Builder, Postgrest};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json::Value;
 use std::cmp::Ordering;
-use std::error::Error;
 
 use crate::models::{
-    HeadToHeadData, LeaderboardEntry, ResultEntry, UserData, UsernameData, Wrapper,
+    AuditEntry, FlaggedResult, LeaderboardEntry, ModerationStatus, PodiumFilter, PuzzleDifficulty,
+    RatingSnapshot, ResultEntry, ResultScore, SeasonStanding, UserData, UsernameData,
 };
+use crate::store::StoreError;
+use crate::util::{build_user_data, merge_new_results, podium_filter_matches, rerank_results};
 
 /// Fetches the results for a given date from the database.
 ///
@@ -18,10 +22,7 @@ use crate::models::{
 /// # Returns
 ///
 /// A `Result` containing a vector of `ResultEntry` structs, or an error if the database query fails.
-pub async fn fetch_results(
-    date: &str,
-    client: &Postgrest,
-) -> Result<Vec<ResultEntry>, Box<dyn Error>> {
+pub async fn fetch_results(date: &str, client: &Postgrest) -> Result<Vec<ResultEntry>, StoreError> {
     let body = client
         .from("results_rust")
         .select("*")
@@ -35,6 +36,275 @@ pub async fn fetch_results(
     Ok(serde_json::from_str::<Vec<ResultEntry>>(&body)?)
 }
 
+/// The maximum number of rows Supabase returns for a single request.
+const PAGE_SIZE: usize = 1000;
+
+/// Runs a query page by page until every matching row has been fetched.
+///
+/// Supabase caps the number of rows returned by a single request, so large tables must be fetched in pages.
+///
+/// # Arguments
+///
+/// * `query` - A closure building the query to run. It's called once per page.
+///
+/// # Returns
+///
+/// A `Result` containing every row returned by the query, or an error if a database query fails.
+async fn fetch_paginated<T: DeserializeOwned>(
+    query: impl Fn() -> Builder,
+) -> Result<Vec<T>, StoreError> {
+    let mut rows = Vec::new();
+
+    loop {
+        let body = query()
+            .range(rows.len(), rows.len() + PAGE_SIZE - 1)
+            .execute()
+            .await?
+            .text()
+            .await?;
+
+        let page: Vec<T> = serde_json::from_str(&body)?;
+        let is_last_page = page.len() < PAGE_SIZE;
+        rows.extend(page);
+
+        if is_last_page {
+            return Ok(rows);
+        }
+    }
+}
+
+/// Replaces every row of a derived table in a single transaction, so readers never see it half written.
+///
+/// The `replace_table_rust` function described in the README deletes the old rows and inserts the new ones
+/// together, which separate delete and insert requests can't do.
+///
+/// # Arguments
+///
+/// * `table` - The name of the table to replace.
+/// * `rows` - The new rows.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` indicating success, or an error if the database query fails, in which case the old rows are kept.
+async fn replace_table<T: Serialize>(
+    table: &str,
+    rows: &[T],
+    client: &Postgrest,
+) -> Result<(), StoreError> {
+    client
+        .rpc(
+            "replace_table_rust",
+            serde_json::to_string(&serde_json::json!({
+                "table_name": table,
+                "rows": rows,
+            }))?,
+        )
+        .execute()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+/// Fetches every result from the database, sorted by date and then time.
+///
+/// # Arguments
+///
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `ResultEntry` structs, or an error if the database query fails.
+pub async fn fetch_all_results(client: &Postgrest) -> Result<Vec<ResultEntry>, StoreError> {
+    fetch_paginated(|| {
+        client
+            .from("results_rust")
+            .select("*")
+            .order("date,time,username")
+    })
+    .await
+}
+
+/// Fetches every result on or after a given date from the database, sorted by date and then time.
+///
+/// # Arguments
+///
+/// * `date` - A string representing the first date in "YYYY-MM-DD" format.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `ResultEntry` structs, or an error if the database query fails.
+pub async fn fetch_results_since(
+    date: &str,
+    client: &Postgrest,
+) -> Result<Vec<ResultEntry>, StoreError> {
+    fetch_paginated(|| {
+        client
+            .from("results_rust")
+            .select("*")
+            .gte("date", date)
+            .order("date,time,username")
+    })
+    .await
+}
+
+/// Inserts new results for a given date and re-ranks every result recorded for that date.
+///
+/// # Arguments
+///
+/// * `date` - A string representing the date in "YYYY-MM-DD" format.
+/// * `new_entries` - The results to insert. Their ranks are ignored and recomputed.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` containing the inserted `ResultEntry` structs with their ranks, or an error if a user already has
+/// a result for the date or a database query fails.
+pub async fn insert_results(
+    date: &str,
+    new_entries: Vec<ResultEntry>,
+    client: &Postgrest,
+) -> Result<Vec<ResultEntry>, StoreError> {
+    let existing_entries = fetch_results(date, client).await?;
+    let (inserted_entries, reranked_entries) =
+        merge_new_results(date, &existing_entries, new_entries)?;
+
+    client
+        .from("results_rust")
+        .insert(serde_json::to_string(&inserted_entries)?)
+        .execute()
+        .await?
+        .error_for_status()?;
+
+    update_ranks(date, &reranked_entries, client).await?;
+
+    Ok(inserted_entries)
+}
+
+/// Writes the ranks of results whose rank changed.
+///
+/// # Arguments
+///
+/// * `date` - A string representing the date in "YYYY-MM-DD" format.
+/// * `entries` - The re-ranked results for `date`.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` indicating success, or an error if a database query fails.
+async fn update_ranks(
+    date: &str,
+    entries: &[ResultEntry],
+    client: &Postgrest,
+) -> Result<(), StoreError> {
+    for entry in entries {
+        client
+            .from("results_rust")
+            .eq("date", date)
+            .eq("username", &entry.username)
+            .update(serde_json::to_string(
+                &serde_json::json!({ "rank": entry.rank }),
+            )?)
+            .execute()
+            .await?
+            .error_for_status()?;
+    }
+
+    Ok(())
+}
+
+/// Changes a user's time for a date and re-ranks every result recorded for that date.
+///
+/// # Arguments
+///
+/// * `date` - A string representing the date in "YYYY-MM-DD" format.
+/// * `username` - A reference to the username as a string.
+/// * `time` - The corrected time, in seconds.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` indicating success, or an error if a database query fails.
+pub async fn update_result_time(
+    date: &str,
+    username: &str,
+    time: i32,
+    client: &Postgrest,
+) -> Result<(), StoreError> {
+    client
+        .from("results_rust")
+        .eq("date", date)
+        .eq("username", username)
+        .update(serde_json::to_string(&serde_json::json!({ "time": time }))?)
+        .execute()
+        .await?
+        .error_for_status()?;
+
+    let entries = fetch_results(date, client).await?;
+    update_ranks(date, &rerank_results(entries), client).await
+}
+
+/// Deletes a user's result for a date and re-ranks the remaining results for that date.
+///
+/// # Arguments
+///
+/// * `date` - A string representing the date in "YYYY-MM-DD" format.
+/// * `username` - A reference to the username as a string.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` indicating success, or an error if a database query fails.
+pub async fn delete_result(
+    date: &str,
+    username: &str,
+    client: &Postgrest,
+) -> Result<(), StoreError> {
+    client
+        .from("results_rust")
+        .eq("date", date)
+        .eq("username", username)
+        .delete()
+        .execute()
+        .await?
+        .error_for_status()?;
+
+    let entries = fetch_results(date, client).await?;
+    update_ranks(date, &rerank_results(entries), client).await
+}
+
+/// Moves every result recorded for one username to another, along with their flags, rating history and scores.
+///
+/// The `merge_users_rust` function described in the README updates every table in a single transaction, so a
+/// failed merge never leaves a user split across both usernames.
+///
+/// # Arguments
+///
+/// * `from` - The username to move results from.
+/// * `to` - The username to move results to.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` indicating success, or an error if the database query fails, in which case nothing is moved.
+pub async fn rename_user(from: &str, to: &str, client: &Postgrest) -> Result<(), StoreError> {
+    client
+        .rpc(
+            "merge_users_rust",
+            serde_json::to_string(&serde_json::json!({
+                "from_username": from,
+                "to_username": to,
+            }))?,
+        )
+        .execute()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
 /// Fetches the most recent crossword date from the database.
 ///
 /// # Arguments
@@ -44,9 +314,7 @@ pub async fn fetch_results(
 /// # Returns
 ///
 /// A `Result` containing the most recent crossword date as a `NaiveDate`, or an error if the database query fails.
-pub async fn fetch_most_recent_crossword_date(
-    client: &Postgrest,
-) -> Result<NaiveDate, Box<dyn Error>> {
+pub async fn fetch_most_recent_crossword_date(client: &Postgrest) -> Result<NaiveDate, StoreError> {
     let body = client
         .from("results_rust")
         .select("date")
@@ -62,9 +330,12 @@ pub async fn fetch_most_recent_crossword_date(
     Ok(NaiveDate::parse_from_str(
         date_data
             .as_array()
-            .ok_or("Couldn't fetch most recent crossword date from database")?[0]["date"]
+            .and_then(|dates| dates.first())
+            .ok_or(StoreError::NoResults)?["date"]
             .as_str()
-            .ok_or("Failed to serialize most recent crossword date as string")?,
+            .ok_or_else(|| {
+                StoreError::UnexpectedResponse(format!("Most recent date isn't a string: {body}"))
+            })?,
         "%Y-%m-%d",
     )?)
 }
@@ -78,9 +349,7 @@ pub async fn fetch_most_recent_crossword_date(
 /// # Returns
 ///
 /// A `Result` containing a vector of usernames as strings, or an error if the database query fails.
-pub async fn fetch_usernames_sorted_by_elo(
-    client: &Postgrest,
-) -> Result<Vec<String>, Box<dyn Error>> {
+pub async fn fetch_usernames_sorted_by_elo(client: &Postgrest) -> Result<Vec<String>, StoreError> {
     let body = client
         .from("all_rust")
         .select("username")
@@ -96,26 +365,132 @@ pub async fn fetch_usernames_sorted_by_elo(
         .collect())
 }
 
-/// Fetches the top 10 results from the database, sorted by time.
+/// Fetches the fastest results matching a `PodiumFilter` from the database, sorted by time, leaving out results
+/// with a flag that hasn't been approved.
 ///
 /// # Arguments
 ///
+/// * `filter` - The `PodiumFilter` to apply.
 /// * `client` - A reference to the Postgrest client.
 ///
 /// # Returns
 ///
-/// A `Result` containing a vector of `ResultEntry` structs, or an error if the database query fails.
-pub async fn fetch_podium_data(client: &Postgrest) -> Result<Vec<ResultEntry>, Box<dyn Error>> {
-    let body = client
-        .from("results_rust")
-        .select("*")
-        .order("time")
-        .execute()
-        .await?
-        .text()
-        .await?;
+/// A `Result` containing a vector of at most `filter.limit` `ResultEntry` structs, or an error if the database
+/// query fails.
+pub async fn fetch_podium_data(
+    filter: &PodiumFilter,
+    client: &Postgrest,
+) -> Result<Vec<ResultEntry>, StoreError> {
+    fetch_filtered_podium(
+        filter,
+        || {
+            client
+                .from("rated_results_rust")
+                .select("*")
+                .order("time,date")
+        },
+        |entry: &ResultEntry| &entry.date,
+    )
+    .await
+}
+
+/// Fetches the stored scores matching a `PodiumFilter` with the best field scores from the database.
+///
+/// # Arguments
+///
+/// * `filter` - The `PodiumFilter` to apply.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of at most `filter.limit` `ResultScore` structs with a field score, best first,
+/// or an error if the database query fails.
+pub async fn fetch_relative_podium_data(
+    filter: &PodiumFilter,
+    client: &Postgrest,
+) -> Result<Vec<ResultScore>, StoreError> {
+    fetch_filtered_podium(
+        filter,
+        || {
+            client
+                .from("result_scores_rust")
+                .select("*")
+                .not("is", "field_score", "null")
+                .order("field_score.desc,date")
+        },
+        |score: &ResultScore| &score.date,
+    )
+    .await
+}
 
-    Ok(serde_json::from_str::<Vec<ResultEntry>>(&body)?[..10].to_vec())
+/// Fetches the first rows of a podium query that match a `PodiumFilter`, in the order the query returns them.
+///
+/// Year and month-of-year filters are applied as a date range in the query. PostgREST can't filter on the day of
+/// the week, so weekday filters and months without a year are applied while paging through the rows, stopping as
+/// soon as the podium is full.
+///
+/// # Arguments
+///
+/// * `filter` - The `PodiumFilter` to apply.
+/// * `query` - A closure building the ordered query to run. It's called once per page.
+/// * `date_of` - A closure returning the date of a row.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of at most `filter.limit` rows, or an error if a database query fails.
+async fn fetch_filtered_podium<T: DeserializeOwned>(
+    filter: &PodiumFilter,
+    query: impl Fn() -> Builder,
+    date_of: impl Fn(&T) -> &str,
+) -> Result<Vec<T>, StoreError> {
+    let date_range = match (filter.year, filter.month) {
+        (Some(year), Some(month)) => Some((
+            NaiveDate::from_ymd_opt(year, month, 1),
+            NaiveDate::from_ymd_opt(year + month as i32 / 12, month % 12 + 1, 1),
+        )),
+        (Some(year), None) => Some((
+            NaiveDate::from_ymd_opt(year, 1, 1),
+            NaiveDate::from_ymd_opt(year + 1, 1, 1),
+        )),
+        _ => None,
+    };
+
+    let query = || match date_range {
+        Some((Some(start), Some(end))) => query()
+            .gte("date", start.format("%Y-%m-%d").to_string())
+            .lt("date", end.format("%Y-%m-%d").to_string()),
+        _ => query(),
+    };
+
+    if filter.weekday.is_none() && (filter.month.is_none() || filter.year.is_some()) {
+        let body = query().limit(filter.limit).execute().await?.text().await?;
+
+        return Ok(serde_json::from_str(&body)?);
+    }
+
+    let mut podium = Vec::new();
+    let mut offset = 0;
+    loop {
+        let body = query()
+            .range(offset, offset + PAGE_SIZE - 1)
+            .execute()
+            .await?
+            .text()
+            .await?;
+
+        let page: Vec<T> = serde_json::from_str(&body)?;
+        let is_last_page = page.len() < PAGE_SIZE;
+        podium.extend(
+            page.into_iter()
+                .filter(|row| podium_filter_matches(filter, date_of(row))),
+        );
+
+        if is_last_page || podium.len() >= filter.limit {
+            podium.truncate(filter.limit);
+            return Ok(podium);
+        }
+        offset += PAGE_SIZE;
+    }
 }
 
 /// Fetches the user data for a given username from the database.
@@ -128,10 +503,7 @@ pub async fn fetch_podium_data(client: &Postgrest) -> Result<Vec<ResultEntry>, B
 /// # Returns
 ///
 /// A `Result` containing a `UserData` struct, or an error if the database query fails.
-pub async fn fetch_user_data(
-    username: &str,
-    client: &Postgrest,
-) -> Result<UserData, Box<dyn Error>> {
+pub async fn fetch_user_data(username: &str, client: &Postgrest) -> Result<UserData, StoreError> {
     let body = client
         .from("results_rust")
         .select("*")
@@ -144,20 +516,7 @@ pub async fn fetch_user_data(
 
     let all_times: Vec<ResultEntry> = serde_json::from_str(&body)?;
 
-    let times_excluding_saturday: Vec<ResultEntry> = all_times
-        .iter()
-        .filter(|entry| {
-            NaiveDate::parse_from_str(entry.date.as_str(), "%Y-%m-%d")
-                .map(|date| date.weekday() != Weekday::Sat)
-                .unwrap_or(false)
-        })
-        .cloned()
-        .collect();
-
-    Ok(UserData {
-        all_times,
-        times_excluding_saturday,
-    })
+    Ok(build_user_data(all_times))
 }
 
 /// Fetches the leaderboard data from the database.
@@ -173,7 +532,7 @@ pub async fn fetch_user_data(
 pub async fn fetch_leaderboard_from_db(
     db_name: &str,
     client: &Postgrest,
-) -> Result<Vec<LeaderboardEntry>, Box<dyn Error>> {
+) -> Result<Vec<LeaderboardEntry>, StoreError> {
     let body = client
         .from(db_name)
         .select("*")
@@ -188,91 +547,467 @@ pub async fn fetch_leaderboard_from_db(
     Ok(leaderboard_data)
 }
 
-/// Fetches the trueskill mu and sigma for a given user from the database.
+/// Replaces the contents of a leaderboard table.
+///
+/// # Arguments
+///
+/// * `db_name` - A string representing the name of the database table to overwrite.
+/// * `entries` - The `LeaderboardEntry` rows to write.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` indicating success, or an error if a database query fails.
+pub async fn replace_leaderboard_in_db(
+    db_name: &str,
+    entries: &[LeaderboardEntry],
+    client: &Postgrest,
+) -> Result<(), StoreError> {
+    replace_table(db_name, entries, client).await
+}
+
+/// Fetches the rating history for a given user from the database.
 ///
 /// # Arguments
 ///
-/// * `db_name` - A string representing the name of the database table to query.
 /// * `username` - A reference to the username as a string.
 /// * `client` - A reference to the Postgrest client.
 ///
 /// # Returns
 ///
-/// A `Result` containing a `LeaderboardEntry` struct, or an error if the database query fails.
-pub async fn fetch_user_trueskill_from_db(
+/// A `Result` containing a vector of `RatingSnapshot` structs sorted by date, or an error if the database query fails.
+pub async fn fetch_rating_history(
     username: &str,
     client: &Postgrest,
-) -> Result<(f64, f64), Box<dyn Error>> {
+) -> Result<Vec<RatingSnapshot>, StoreError> {
+    fetch_paginated(|| {
+        client
+            .from("rating_history_rust")
+            .select("*")
+            .eq("username", username)
+            .order("date")
+    })
+    .await
+}
+
+/// Replaces the contents of the rating history table.
+///
+/// # Arguments
+///
+/// * `history` - The `RatingSnapshot` rows to write.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` indicating success, or an error if a database query fails.
+pub async fn replace_rating_history_in_db(
+    history: &[RatingSnapshot],
+    client: &Postgrest,
+) -> Result<(), StoreError> {
+    replace_table("rating_history_rust", history, client).await
+}
+
+/// Fetches the stored final standings of a season from the database.
+///
+/// # Arguments
+///
+/// * `season_id` - The id of the season.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `SeasonStanding` structs sorted by rank, or an error if the database query
+/// fails.
+pub async fn fetch_season_standings(
+    season_id: &str,
+    client: &Postgrest,
+) -> Result<Vec<SeasonStanding>, StoreError> {
+    fetch_paginated(|| {
+        client
+            .from("season_standings_rust")
+            .select("*")
+            .eq("season_id", season_id)
+            .order("rank")
+    })
+    .await
+}
+
+/// Fetches the top three of every season with stored standings from the database.
+///
+/// # Arguments
+///
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `SeasonStanding` structs sorted by season and then rank, or an error if
+/// the database query fails.
+pub async fn fetch_season_podiums(client: &Postgrest) -> Result<Vec<SeasonStanding>, StoreError> {
+    fetch_paginated(|| {
+        client
+            .from("season_standings_rust")
+            .select("*")
+            .lte("rank", "3")
+            .order("season_id,rank")
+    })
+    .await
+}
+
+/// Replaces the contents of the season standings table.
+///
+/// # Arguments
+///
+/// * `standings` - The `SeasonStanding` rows to write.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` indicating success, or an error if a database query fails.
+pub async fn replace_season_standings_in_db(
+    standings: &[SeasonStanding],
+    client: &Postgrest,
+) -> Result<(), StoreError> {
+    replace_table("season_standings_rust", standings, client).await
+}
+
+/// Fetches the stored difficulty of every puzzle from the database.
+///
+/// # Arguments
+///
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `PuzzleDifficulty` structs, hardest first, or an error if the database query
+/// fails.
+pub async fn fetch_puzzle_difficulties(
+    client: &Postgrest,
+) -> Result<Vec<PuzzleDifficulty>, StoreError> {
+    fetch_paginated(|| {
+        client
+            .from("puzzle_difficulties_rust")
+            .select("*")
+            .order("difficulty.desc,date")
+    })
+    .await
+}
+
+/// Fetches the stored difficulty of the puzzle for a date from the database.
+///
+/// # Arguments
+///
+/// * `date` - A string representing the date in "YYYY-MM-DD" format.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` containing the `PuzzleDifficulty`, or `None` if the puzzle hasn't been scored, or an error if the
+/// database query fails.
+pub async fn fetch_puzzle_difficulty(
+    date: &str,
+    client: &Postgrest,
+) -> Result<Option<PuzzleDifficulty>, StoreError> {
     let body = client
-        .from("all_rust")
+        .from("puzzle_difficulties_rust")
         .select("*")
-        .eq("username", username)
+        .eq("date", date)
         .execute()
         .await?
         .text()
         .await?;
 
-    let user_data: LeaderboardEntry = serde_json::from_str::<Vec<LeaderboardEntry>>(&body)?
-        .first()
-        .cloned()
-        .ok_or("Couldn't find user in database")?;
+    Ok(serde_json::from_str::<Vec<PuzzleDifficulty>>(&body)?
+        .into_iter()
+        .next())
+}
 
-    Ok((user_data.mu, user_data.sigma))
+/// Fetches the stored difficulty of every puzzle on or after a given date from the database.
+///
+/// # Arguments
+///
+/// * `date` - A string representing the first date in "YYYY-MM-DD" format.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `PuzzleDifficulty` structs sorted by date, or an error if the database query
+/// fails.
+pub async fn fetch_puzzle_difficulties_since(
+    date: &str,
+    client: &Postgrest,
+) -> Result<Vec<PuzzleDifficulty>, StoreError> {
+    fetch_paginated(|| {
+        client
+            .from("puzzle_difficulties_rust")
+            .select("*")
+            .gte("date", date)
+            .order("date")
+    })
+    .await
 }
 
-/// Fetches the head-to-head data for two users from the database.
+/// Replaces the contents of the puzzle difficulties table.
 ///
 /// # Arguments
 ///
-/// * `user1` - A string representing the username of the first user.
-/// * `user2` - A string representing the username of the second user.
+/// * `difficulties` - The `PuzzleDifficulty` rows to write.
 /// * `client` - A reference to the Postgrest client.
 ///
 /// # Returns
 ///
-/// A `Result` containing a `HeadToHeadData` struct, or an error if the database query fails.
-pub async fn fetch_h2h_data(
-    user1: String,
-    user2: String,
+/// A `Result` indicating success, or an error if a database query fails.
+pub async fn replace_puzzle_difficulties_in_db(
+    difficulties: &[PuzzleDifficulty],
+    client: &Postgrest,
+) -> Result<(), StoreError> {
+    replace_table("puzzle_difficulties_rust", difficulties, client).await
+}
+
+/// Fetches the stored scores of every result for a date from the database.
+///
+/// # Arguments
+///
+/// * `date` - A string representing the date in "YYYY-MM-DD" format.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `ResultScore` structs, or an error if the database query fails.
+pub async fn fetch_result_scores(
+    date: &str,
     client: &Postgrest,
-) -> Result<HeadToHeadData, Box<dyn Error>> {
+) -> Result<Vec<ResultScore>, StoreError> {
+    fetch_paginated(|| {
+        client
+            .from("result_scores_rust")
+            .select("*")
+            .eq("date", date)
+            .order("username")
+    })
+    .await
+}
+
+/// Fetches a user's stored scores with the best field scores from the database.
+///
+/// # Arguments
+///
+/// * `username` - A reference to the username as a string.
+/// * `limit` - The maximum number of scores to fetch.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `ResultScore` structs with a field score, best first, or an error if the
+/// database query fails.
+pub async fn fetch_best_result_scores(
+    username: &str,
+    limit: usize,
+    client: &Postgrest,
+) -> Result<Vec<ResultScore>, StoreError> {
     let body = client
-        .rpc(
-            "get_h2h_stats",
-            serde_json::to_string(&serde_json::json!({
-                "user1": user1,
-                "user2": user2,
-            }))?,
-        )
+        .from("result_scores_rust")
+        .select("*")
+        .eq("username", username)
+        .not("is", "field_score", "null")
+        .order("field_score.desc")
+        .limit(limit)
         .execute()
         .await?
         .text()
         .await?;
 
-    let h2h_data: HeadToHeadData = serde_json::from_str(&body)
-        .map_err(|e| format!("JSON parsing error: {e}, body: {body}"))
-        .map(|wrapper: Wrapper<HeadToHeadData>| wrapper.inner)?;
+    Ok(serde_json::from_str(&body)?)
+}
 
-    let speed_verb = if h2h_data.avg_time_difference < 0.0 {
-        "faster"
-    } else {
-        "slower"
-    };
+/// Replaces the contents of the result scores table.
+///
+/// # Arguments
+///
+/// * `scores` - The `ResultScore` rows to write.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` indicating success, or an error if a database query fails.
+pub async fn replace_result_scores_in_db(
+    scores: &[ResultScore],
+    client: &Postgrest,
+) -> Result<(), StoreError> {
+    replace_table("result_scores_rust", scores, client).await
+}
+
+/// Fetches every flagged result from the database.
+///
+/// # Arguments
+///
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `FlaggedResult` structs, most recent date first, or an error if the database
+/// query fails.
+pub async fn fetch_flagged_results(client: &Postgrest) -> Result<Vec<FlaggedResult>, StoreError> {
+    fetch_paginated(|| {
+        client
+            .from("flagged_results_rust")
+            .select("*")
+            .order("date.desc,username")
+    })
+    .await
+}
+
+/// Adds results to the moderation queue.
+///
+/// # Arguments
+///
+/// * `flags` - The `FlaggedResult` rows to write.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` indicating success, or an error if a database query fails.
+pub async fn insert_flagged_results(
+    flags: &[FlaggedResult],
+    client: &Postgrest,
+) -> Result<(), StoreError> {
+    for chunk in flags.chunks(PAGE_SIZE) {
+        client
+            .from("flagged_results_rust")
+            .insert(serde_json::to_string(chunk)?)
+            .execute()
+            .await?
+            .error_for_status()?;
+    }
+
+    Ok(())
+}
+
+/// Sets the moderation status of a user's flagged result for a date.
+///
+/// # Arguments
+///
+/// * `date` - A string representing the date in "YYYY-MM-DD" format.
+/// * `username` - A reference to the username as a string.
+/// * `status` - The new `ModerationStatus`.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` indicating success, or an error if the database query fails.
+pub async fn update_flag_status(
+    date: &str,
+    username: &str,
+    status: ModerationStatus,
+    client: &Postgrest,
+) -> Result<(), StoreError> {
+    client
+        .from("flagged_results_rust")
+        .eq("date", date)
+        .eq("username", username)
+        .update(serde_json::to_string(
+            &serde_json::json!({ "status": status }),
+        )?)
+        .execute()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+/// Deletes a user's flagged result for a date, if there is one.
+///
+/// # Arguments
+///
+/// * `date` - A string representing the date in "YYYY-MM-DD" format.
+/// * `username` - A reference to the username as a string.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` indicating success, or an error if the database query fails.
+pub async fn delete_flagged_result(
+    date: &str,
+    username: &str,
+    client: &Postgrest,
+) -> Result<(), StoreError> {
+    client
+        .from("flagged_results_rust")
+        .eq("date", date)
+        .eq("username", username)
+        .delete()
+        .execute()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
 
-    let time_diff_description = format!(
-        "<a class=\"user1\" href=\"/user/{}\">{}</a> is {:.1} seconds {} than <a class=\"user2\" href=\"/user/{}\">{}</a> on average.",
-        user1,
-        user1,
-        h2h_data.avg_time_difference.abs(),
-        speed_verb,
-        user2,
-        user2,
-    );
-
-    Ok(HeadToHeadData {
-        user1,
-        user2,
-        time_diff_description,
-        ..h2h_data
+/// Fetches the audit log from the database.
+///
+/// # Arguments
+///
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of `AuditEntry` structs, most recent first, or an error if the database query
+/// fails.
+pub async fn fetch_audit_log(client: &Postgrest) -> Result<Vec<AuditEntry>, StoreError> {
+    fetch_paginated(|| {
+        client
+            .from("audit_log_rust")
+            .select("*")
+            .order("timestamp.desc,id.desc")
     })
+    .await
+}
+
+/// Adds an entry to the audit log.
+///
+/// # Arguments
+///
+/// * `entry` - The `AuditEntry` to write.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` indicating success, or an error if the database query fails.
+pub async fn insert_audit_entry(entry: &AuditEntry, client: &Postgrest) -> Result<(), StoreError> {
+    client
+        .from("audit_log_rust")
+        .insert(serde_json::to_string(entry)?)
+        .execute()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+/// Fetches the trueskill mu and sigma for a given user from the database.
+///
+/// # Arguments
+///
+/// * `username` - A reference to the username as a string.
+/// * `client` - A reference to the Postgrest client.
+///
+/// # Returns
+///
+/// A `Result` containing the user's `(mu, sigma)`, or `None` if they aren't on the all-time leaderboard, or an
+/// error if the database query fails.
+pub async fn fetch_user_trueskill_from_db(
+    username: &str,
+    client: &Postgrest,
+) -> Result<Option<(f64, f64)>, StoreError> {
+    let body = client
+        .from("all_rust")
+        .select("*")
+        .eq("username", username)
+        .execute()
+        .await?
+        .text()
+        .await?;
+
+    Ok(serde_json::from_str::<Vec<LeaderboardEntry>>(&body)?
+        .first()
+        .map(|user_data| (user_data.mu, user_data.sigma)))
 }
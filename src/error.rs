@@ -0,0 +1,178 @@
+use std::fmt::Display;
+use thiserror::Error;
+
+use crate::parser::ParseError;
+use crate::store::StoreError;
+use crate::util::{
+    ComparisonError, CorrectionError, LeaderboardWindowError, PodiumFilterError, SeasonError,
+    SubmissionError,
+};
+
+pub type AppResult<T> = Result<T, AppError>;
+
+/// Everything that can go wrong while handling a request, grouped by the response it should produce.
+#[derive(Debug, Error)]
+pub enum AppError {
+    #[error("Couldn't find a user named {0}")]
+    UserNotFound(String),
+    #[error("There's no page at {0}")]
+    PageNotFound(String),
+    #[error("There's no season with the id {0}")]
+    SeasonNotFound(String),
+    #[error("{1} has no flagged result for {0}")]
+    FlagNotFound(String, String),
+    #[error("{1} has no result for {0}")]
+    ResultNotFound(String, String),
+    #[error("{0} isn't a valid date. Dates must be in YYYY-MM-DD format.")]
+    InvalidDate(String),
+    #[error("{0}")]
+    BadRequest(String),
+    #[error(transparent)]
+    PodiumFilter(#[from] PodiumFilterError),
+    #[error(transparent)]
+    LeaderboardWindow(#[from] LeaderboardWindowError),
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error(transparent)]
+    Comparison(#[from] ComparisonError),
+    #[error(transparent)]
+    Submission(#[from] SubmissionError),
+    #[error(transparent)]
+    Correction(#[from] CorrectionError),
+    #[error(transparent)]
+    Season(#[from] SeasonError),
+    #[error("Missing or invalid secret")]
+    Unauthorized,
+    #[error("Database error: {0}")]
+    Database(String),
+    #[error("Template error: {0}")]
+    Template(#[from] askama::Error),
+    #[error("Worker error: {0}")]
+    Worker(#[from] worker::Error),
+}
+
+impl AppError {
+    /// The HTTP status code to respond with.
+    pub fn status(&self) -> u16 {
+        match self {
+            AppError::UserNotFound(_)
+            | AppError::PageNotFound(_)
+            | AppError::SeasonNotFound(_)
+            | AppError::FlagNotFound(..)
+            | AppError::ResultNotFound(..) => 404,
+            AppError::LeaderboardWindow(LeaderboardWindowError::InvalidResultDate(_)) => 500,
+            AppError::InvalidDate(_)
+            | AppError::BadRequest(_)
+            | AppError::PodiumFilter(_)
+            | AppError::LeaderboardWindow(_)
+            | AppError::Parse(_)
+            | AppError::Comparison(_) => 400,
+            AppError::Submission(SubmissionError::AlreadySubmitted(..)) => 409,
+            AppError::Submission(_) => 400,
+            AppError::Correction(CorrectionError::MergeConflict(..)) => 409,
+            AppError::Correction(_) => 400,
+            AppError::Unauthorized => 401,
+            AppError::Database(_) => 502,
+            AppError::Season(_) | AppError::Template(_) | AppError::Worker(_) => 500,
+        }
+    }
+
+    /// The reason phrase for `status`, used as the title of error pages.
+    pub fn title(&self) -> &'static str {
+        match self.status() {
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            404 => "Not Found",
+            409 => "Conflict",
+            502 => "Bad Gateway",
+            _ => "Internal Server Error",
+        }
+    }
+
+    /// The message to show the client. Server-side failures get a generic message, since their details are only
+    /// useful in the logs.
+    pub fn public_message(&self) -> String {
+        match self.status() {
+            502 => String::from("Couldn't reach the database. Please try again later."),
+            500.. => String::from("Something went wrong on our end. Please try again later."),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Builds a `map_err` callback that wraps a storage error in `AppError::Database`, prefixed with `context`.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - What was being attempted, e.g. "Couldn't fetch results from database".
+    ///
+    /// # Returns
+    ///
+    /// A closure converting the storage error into an `AppError`.
+    pub fn database<E: Display>(context: &'static str) -> impl FnOnce(E) -> AppError {
+        move |e| AppError::Database(format!("{context}: {e}"))
+    }
+}
+
+/// Storage errors are database errors, except for results the store rejected, which keep their own status.
+impl From<StoreError> for AppError {
+    fn from(e: StoreError) -> Self {
+        match e {
+            StoreError::Submission(submission_error) => AppError::Submission(submission_error),
+            e => AppError::Database(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        assert_eq!(AppError::UserNotFound(String::from("alice")).status(), 404);
+        assert_eq!(
+            AppError::InvalidDate(String::from("2026-13-01")).status(),
+            400
+        );
+        assert_eq!(
+            AppError::from(PodiumFilterError::InvalidLimit).status(),
+            400
+        );
+        assert_eq!(
+            AppError::from(LeaderboardWindowError::InvalidDays).status(),
+            400
+        );
+        assert_eq!(
+            AppError::from(LeaderboardWindowError::InvalidResultDate(String::from(
+                "2026-13-01"
+            )))
+            .status(),
+            500
+        );
+        assert_eq!(AppError::Database(String::from("timeout")).status(), 502);
+        assert_eq!(
+            AppError::Database(String::from("timeout")).public_message(),
+            "Couldn't reach the database. Please try again later."
+        );
+        assert_eq!(
+            AppError::from(worker::Error::RustError(String::from("oops"))).status(),
+            500
+        );
+    }
+
+    #[test]
+    fn test_from_store_error() {
+        let already_submitted = StoreError::from(SubmissionError::AlreadySubmitted(
+            String::from("alice"),
+            String::from("2026-10-16"),
+        ));
+        assert_eq!(AppError::from(already_submitted).status(), 409);
+
+        let error = AppError::from(StoreError::Batch(String::from("connection reset")));
+        assert_eq!(error.status(), 502);
+        assert_eq!(
+            error.to_string(),
+            "Database error: A batch of statements failed: connection reset"
+        );
+    }
+}
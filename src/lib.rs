@@ -1,96 +1,1088 @@
 use askama::Template;
-use chrono::Duration;
-use database::fetch_user_trueskill_from_db;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use postgrest::Postgrest;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
 use util::compute_win_probability;
-use worker::{event, Context, Env, Request, Response, Result, RouteContext, Router};
+use worker::{
+    console_error, event, Context, Env, FormData, Headers, Request, Response, Result, RouteContext,
+    Router, ScheduleContext, ScheduledEvent,
+};
 
+mod d1_store;
 mod database;
+mod error;
+#[cfg(test)]
+mod memory_store;
 mod models;
+mod parser;
+mod rating;
+mod store;
 mod templates;
 mod util;
 
-use crate::database::{
-    fetch_h2h_data, fetch_leaderboard_from_db, fetch_most_recent_crossword_date, fetch_podium_data,
-    fetch_results, fetch_user_data, fetch_usernames_sorted_by_elo,
+use crate::d1_store::D1Store;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    AuditAction, AuditEntry, ComparisonResponse, Correction, DailyResults, ErrorResponse,
+    FavoritesResponse, FinishPrediction, FlaggedResult, HeadToHeadMatrix, HeadToHeadResponse,
+    LeaderboardEntry, LeaderboardWindow, ModerationStatus, PodiumFilter, PredictionOutcome,
+    PuzzleDifficulty, ResultEntry, ResultScore, ScoredResult, Season, SeasonResults,
+    SeasonSchedule, SeasonStanding, SeasonStatus, Submission, TimePrediction, UserData,
+    UserResponse, UserStats,
+};
+use crate::parser::parse_share_text;
+use crate::rating::{
+    compute_leaderboard, compute_leaderboard_with_history, predict_finishing_order, Rating,
+    RatingSystem, RatingSystemKind, TrueSkill,
 };
+use crate::store::{ResultStore, Store};
 use crate::templates::{
-    HeadToHeadTemplate, HistoryTemplate, LeaderboardTemplate, PodiumTemplate, RecentTemplate,
-    UserTemplate, CSS_STYLES,
+    AdminLoginTemplate, AdminTemplate, CompareTemplate, ErrorTemplate, HeadToHeadMatrixTemplate,
+    HeadToHeadTemplate, HistoryTemplate, LeaderboardTemplate, ModerationTemplate, PodiumTemplate,
+    PuzzlesTemplate, RecentTemplate, SeasonTemplate, SeasonsTemplate, SubmitTemplate, UserTemplate,
+    CSS_STYLES,
 };
-use crate::util::{generate_box_plot_html, generate_scatter_plot_html};
+use crate::util::{
+    build_user_data, compute_h2h_matches, compute_h2h_matrix, compute_h2h_stats,
+    compute_prediction_outcomes, compute_puzzle_difficulties, compute_season_standings,
+    compute_user_stats, compute_weekday_stats, compute_win_probability_matrix, decode_path_segment,
+    describe_leaderboard_window, describe_podium_filter, detect_suspicious_results,
+    exclude_flagged_results, filter_to_recent_days, filter_to_window, generate_box_plot_html,
+    generate_elo_plot_html, generate_h2h_plot_html, generate_prediction_plot_html,
+    generate_scatter_plot_html, generate_weekday_box_plot_html, leaderboard_link, list_seasons,
+    merge_new_results, parse_compared_users, parse_corrected_time, parse_leaderboard_window,
+    parse_podium_filter, predict_solve_times, score_results, season_status, secrets_match,
+    summarize_compared_player, summarize_prediction_accuracy, validate_merge, validate_submission,
+    weekday_name, ComparisonError, SeasonError, WEEKDAYS,
+};
+
+/// The number of puzzles in each list on the puzzles page.
+const PUZZLES_SHOWN: usize = 10;
+
+/// The number of results in the best relative performances list on user pages.
+const BEST_RELATIVE_RESULTS_SHOWN: usize = 5;
+
+/// The number of players in the favorites list on the leaderboard.
+const FAVORITES_SHOWN: usize = 5;
+
+/// The number of most recent predictions the recent prediction accuracy on user pages covers.
+const RECENT_PREDICTIONS: usize = 30;
+
+/// The number of most recent changes shown in the audit log on the admin page.
+const AUDIT_LOG_SHOWN: usize = 50;
 
-fn get_db_client<T>(ctx: &RouteContext<T>) -> Result<Postgrest> {
-    let url = ctx.secret("SUPABASE_API_URL")?.to_string();
-    let key = ctx.secret("SUPABASE_API_KEY")?.to_string();
+/// The cookie the sign-in form remembers the admin secret in.
+const ADMIN_COOKIE: &str = "admin_secret";
+
+/// How long the admin cookie lasts, in seconds.
+const ADMIN_COOKIE_MAX_AGE: i64 = 7 * 24 * 60 * 60;
+
+/// The admin pages, which are the only places the sign-in form returns to. The first is the default.
+const ADMIN_PAGES: [&str; 2] = ["/admin", "/admin/moderation"];
+
+/// The leaderboard windows offered in the leaderboard's filter menu.
+const WINDOW_PRESETS: [LeaderboardWindow; 6] = [
+    LeaderboardWindow::All,
+    LeaderboardWindow::LastDays(7),
+    LeaderboardWindow::LastDays(30),
+    LeaderboardWindow::LastDays(90),
+    LeaderboardWindow::ThisMonth,
+    LeaderboardWindow::ThisYear,
+];
+
+/// The rolling-window leaderboards the rebuild stores, and the number of days each one covers.
+const LEADERBOARD_WINDOWS: [(&str, i64); 2] = [("last_30", 30), ("last_90", 90)];
+
+fn get_db_client(env: &Env) -> Result<Postgrest> {
+    let url = env.secret("SUPABASE_API_URL")?.to_string();
+    let key = env.secret("SUPABASE_API_KEY")?.to_string();
 
     let client = Postgrest::new(url).insert_header("apikey", key);
 
     Ok(client)
 }
 
+/// Connects to the storage backend named by the `STORAGE_BACKEND` var, defaulting to Supabase.
+fn get_store(env: &Env) -> AppResult<Store> {
+    let backend = env
+        .var("STORAGE_BACKEND")
+        .map_or_else(|_| String::from("supabase"), |var| var.to_string());
+
+    match backend.as_str() {
+        "supabase" => Ok(Store::Supabase(get_db_client(env)?)),
+        "d1" => Ok(Store::D1(D1Store::new(env.d1("DB")?))),
+        _ => Err(AppError::Database(format!(
+            "Unknown storage backend: {backend}"
+        ))),
+    }
+}
+
+/// Reads the season schedule from the `SEASON_LENGTH` var, defaulting to quarterly seasons. Custom seasons are
+/// read from the `CUSTOM_SEASONS` var.
+fn get_season_schedule(env: &Env) -> AppResult<SeasonSchedule> {
+    let length = env
+        .var("SEASON_LENGTH")
+        .map_or_else(|_| String::from("quarterly"), |var| var.to_string());
+
+    match length.as_str() {
+        "monthly" => Ok(SeasonSchedule::Monthly),
+        "quarterly" => Ok(SeasonSchedule::Quarterly),
+        "custom" => Ok(SeasonSchedule::Custom(env.object_var("CUSTOM_SEASONS")?)),
+        _ => Err(SeasonError::UnknownLength(length).into()),
+    }
+}
+
+fn is_valid_secret<T>(secret: &str, ctx: &RouteContext<T>) -> AppResult<bool> {
+    let expected = ctx.secret("SUBMIT_SECRET")?.to_string();
+
+    Ok(secrets_match(secret.trim(), &expected))
+}
+
+/// Reads the secret passed as a bearer token in the `Authorization` header, if there is one.
+fn bearer_secret(req: &Request) -> AppResult<Option<String>> {
+    Ok(req
+        .headers()
+        .get("Authorization")?
+        .and_then(|header| header.strip_prefix("Bearer ").map(str::to_string)))
+}
+
+fn is_authorized<T>(req: &Request, ctx: &RouteContext<T>) -> AppResult<bool> {
+    match bearer_secret(req)? {
+        Some(secret) => is_valid_secret(&secret, ctx),
+        None => Ok(false),
+    }
+}
+
+/// Reads the value of a cookie from a `Cookie` header.
+fn cookie_value(header: &str, name: &str) -> Option<String> {
+    header.split(';').find_map(|cookie| {
+        let (key, value) = cookie.trim().split_once('=')?;
+        (key == name).then(|| decode_path_segment(value)).flatten()
+    })
+}
+
+/// Reads the admin secret passed as a bearer token, or remembered in `ADMIN_COOKIE` by the sign-in form.
+fn admin_secret(req: &Request) -> AppResult<Option<String>> {
+    if let Some(secret) = bearer_secret(req)? {
+        return Ok(Some(secret));
+    }
+
+    Ok(req
+        .headers()
+        .get("Cookie")?
+        .and_then(|header| cookie_value(&header, ADMIN_COOKIE)))
+}
+
+/// Returns `AppError::Unauthorized` unless `secret` matches the admin secret `expected`.
+fn check_admin_secret(secret: Option<&str>, expected: &str) -> AppResult<()> {
+    match secret {
+        Some(secret) if secrets_match(secret.trim(), expected) => Ok(()),
+        _ => Err(AppError::Unauthorized),
+    }
+}
+
+fn authorize_admin<T>(req: &Request, ctx: &RouteContext<T>) -> AppResult<()> {
+    let expected = ctx.secret("ADMIN_SECRET")?.to_string();
+
+    check_admin_secret(admin_secret(req)?.as_deref(), &expected)
+}
+
+/// Builds the `Set-Cookie` header value that remembers the admin secret for the admin pages.
+fn admin_cookie(secret: &str) -> String {
+    format!(
+        "{ADMIN_COOKIE}={}; Path=/admin; Max-Age={ADMIN_COOKIE_MAX_AGE}; HttpOnly; Secure; SameSite=Strict",
+        utf8_percent_encode(secret.trim(), NON_ALPHANUMERIC)
+    )
+}
+
+/// The admin page to return to after signing in, which is `/admin` unless `next` is another admin page.
+fn admin_redirect_path(next: Option<String>) -> &'static str {
+    next.and_then(|next| ADMIN_PAGES.into_iter().find(|page| *page == next))
+        .unwrap_or(ADMIN_PAGES[0])
+}
+
+/// Renders the sign-in form for the admin pages, with a 401 status.
+fn admin_login_page(next: &str, error: Option<String>) -> Result<Response> {
+    let html = AdminLoginTemplate {
+        next: next.to_string(),
+        error,
+    }
+    .render()
+    .map_err(|e| e.to_string())?;
+
+    Ok(Response::from_html(html)?.with_status(401))
+}
+
+/// Renders an admin page, or the sign-in form if the request doesn't carry the admin secret.
+async fn admin_page<T>(
+    req: &Request,
+    ctx: &RouteContext<T>,
+    path: &str,
+    page: impl Future<Output = AppResult<String>>,
+) -> Result<Response> {
+    match authorize_admin(req, ctx) {
+        Ok(()) => html_response(page).await,
+        Err(AppError::Unauthorized) => admin_login_page(path, None),
+        Err(e) => error_page(&e),
+    }
+}
+
+async fn handle_admin_login<T>(req: &mut Request, ctx: &RouteContext<T>) -> Result<Response> {
+    let form = req.form_data().await?;
+    let secret = form.get_field("secret").unwrap_or_default();
+    let next = admin_redirect_path(form.get_field("next"));
+
+    let expected = match ctx.secret("ADMIN_SECRET") {
+        Ok(expected) => expected.to_string(),
+        Err(e) => return error_page(&e.into()),
+    };
+    if let Err(e) = check_admin_secret(Some(&secret), &expected) {
+        return admin_login_page(next, Some(e.to_string()));
+    }
+
+    let headers = Headers::new();
+    headers.set("Location", next)?;
+    headers.set("Set-Cookie", &admin_cookie(&secret))?;
+
+    Ok(Response::empty()?.with_status(303).with_headers(headers))
+}
+
+/// Reads a percent-decoded route parameter.
+fn route_param<T>(ctx: &RouteContext<T>, name: &str) -> AppResult<String> {
+    ctx.param(name)
+        .and_then(|value| decode_path_segment(value))
+        .ok_or_else(|| AppError::BadRequest(format!("Couldn't process {name} parameter")))
+}
+
+/// Logs errors that weren't caused by the client.
+fn log_error(e: &AppError) {
+    if e.status() >= 500 {
+        console_error!("{e}");
+    }
+}
+
+/// Renders the error page for `e`, with the matching status code.
+fn error_page(e: &AppError) -> Result<Response> {
+    log_error(e);
+
+    let html = ErrorTemplate {
+        status: e.status(),
+        title: e.title(),
+        message: e.public_message(),
+    }
+    .render()
+    .map_err(|e| e.to_string())?;
+
+    Ok(Response::from_html(html)?.with_status(e.status()))
+}
+
+/// Responds with a JSON `{"error": ...}` body for `e`, with the matching status code.
+fn json_error(e: &AppError) -> Result<Response> {
+    log_error(e);
+
+    Ok(Response::from_json(&ErrorResponse {
+        error: e.public_message(),
+    })?
+    .with_status(e.status()))
+}
+
+/// Responds with the page rendered by `page`, or with an error page if rendering failed.
+async fn html_response(page: impl Future<Output = AppResult<String>>) -> Result<Response> {
+    match page.await {
+        Ok(html) => Response::from_html(html),
+        Err(e) => error_page(&e),
+    }
+}
+
+/// Responds with the JSON serialization of the value produced by `data`, or with a JSON error.
+async fn json_response<T: Serialize>(data: impl Future<Output = AppResult<T>>) -> Result<Response> {
+    match data.await {
+        Ok(data) => Response::from_json(&data),
+        Err(e) => json_error(&e),
+    }
+}
+
 #[event(fetch)]
 async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response> {
     let router = Router::new();
     router
-        .get_async("/", |_req, ctx| async move {
-            handle_index(&ctx, &get_db_client(&ctx)?).await
+        .get_async("/", |req, ctx| async move {
+            html_response(async {
+                let (window, system) = parse_leaderboard_params(&req, &ctx)?;
+                let today = Utc::now().date_naive();
+                render_index(window, system, today, &get_store(&ctx.env)?).await
+            })
+            .await
         })
-        .get_async("/index/:db_name", |_req, ctx| async move {
-            handle_index(&ctx, &get_db_client(&ctx)?).await
+        .get_async("/index/:db_name", |req, ctx| async move {
+            html_response(async {
+                let (window, system) = parse_leaderboard_params(&req, &ctx)?;
+                let today = Utc::now().date_naive();
+                render_index(window, system, today, &get_store(&ctx.env)?).await
+            })
+            .await
         })
-        .get_async("/podium", |_req, ctx| async move {
-            handle_podium(&get_db_client(&ctx)?).await
+        .get_async("/podium", |req, ctx| async move {
+            html_response(async {
+                let filter = parse_podium_filter(req.url()?.query_pairs())?;
+                render_podium(filter, false, &get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .get_async("/podium/relative", |req, ctx| async move {
+            html_response(async {
+                let filter = parse_podium_filter(req.url()?.query_pairs())?;
+                render_podium(filter, true, &get_store(&ctx.env)?).await
+            })
+            .await
         })
         .get_async("/user/:username", |_req, ctx| async move {
-            handle_user(&ctx, &get_db_client(&ctx)?).await
+            html_response(async {
+                let username = route_param(&ctx, "username")?;
+                render_user(username, &get_store(&ctx.env)?).await
+            })
+            .await
         })
         .get_async("/history/:date", |_req, ctx| async move {
-            handle_history(&ctx, &get_db_client(&ctx)?).await
+            html_response(async {
+                let date = route_param(&ctx, "date")?;
+                render_history(date, &get_store(&ctx.env)?).await
+            })
+            .await
         })
         .get_async("/recent", |_req, ctx| async move {
-            handle_recent(&get_db_client(&ctx)?).await
+            html_response(async { render_recent(&get_store(&ctx.env)?).await }).await
+        })
+        .get_async("/puzzles", |_req, ctx| async move {
+            html_response(async { render_puzzles(&get_store(&ctx.env)?).await }).await
+        })
+        .get_async("/seasons", |_req, ctx| async move {
+            html_response(async {
+                let schedule = get_season_schedule(&ctx.env)?;
+                let today = Utc::now().date_naive();
+                render_seasons(&schedule, today, &get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .get_async("/seasons/:id", |_req, ctx| async move {
+            html_response(async {
+                let id = route_param(&ctx, "id")?;
+                let schedule = get_season_schedule(&ctx.env)?;
+                let today = Utc::now().date_naive();
+                render_season(&id, &schedule, today, &get_store(&ctx.env)?).await
+            })
+            .await
         })
         .get_async("/h2h", |_req, ctx| async move {
-            handle_h2h(&ctx, &get_db_client(&ctx)?).await
+            html_response(async { render_h2h(None, &get_store(&ctx.env)?).await }).await
+        })
+        .get_async("/compare", |req, ctx| async move {
+            html_response(async {
+                let usernames = parse_compare_params(&req)?;
+                render_compare(usernames, &get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .get_async("/h2h/matrix", |req, ctx| async move {
+            html_response(async {
+                let top = parse_matrix_size(&req)?;
+                render_h2h_matrix(top, &get_store(&ctx.env)?).await
+            })
+            .await
         })
         .get_async("/h2h/:user1/:user2", |_req, ctx| async move {
-            handle_h2h(&ctx, &get_db_client(&ctx)?).await
+            html_response(async {
+                let users = (route_param(&ctx, "user1")?, route_param(&ctx, "user2")?);
+                render_h2h(Some(users), &get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .get_async("/api/leaderboard", |req, ctx| async move {
+            json_response(async {
+                let (window, system) = parse_leaderboard_params(&req, &ctx)?;
+                let today = Utc::now().date_naive();
+                load_leaderboard(window, system, today, &get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .get_async("/api/leaderboard/:db_name", |req, ctx| async move {
+            json_response(async {
+                let (window, system) = parse_leaderboard_params(&req, &ctx)?;
+                let today = Utc::now().date_naive();
+                load_leaderboard(window, system, today, &get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .get_async("/api/user/:username", |_req, ctx| async move {
+            json_response(async {
+                let username = route_param(&ctx, "username")?;
+                api_user(username, &get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .get_async("/api/history/:date", |_req, ctx| async move {
+            json_response(async {
+                let date = route_param(&ctx, "date")?;
+                fetch_history(&date, &get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .get_async("/api/compare", |req, ctx| async move {
+            json_response(async {
+                let usernames = parse_compare_params(&req)?;
+                api_compare(usernames, &get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .get_async("/api/h2h/matrix", |req, ctx| async move {
+            json_response(async {
+                let top = parse_matrix_size(&req)?;
+                fetch_h2h_matrix(top, &get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .get_async("/api/h2h/:user1/:user2", |_req, ctx| async move {
+            json_response(async {
+                let (user1, user2) = (route_param(&ctx, "user1")?, route_param(&ctx, "user2")?);
+                api_h2h(user1, user2, &get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .get_async("/api/favorites", |_req, ctx| async move {
+            json_response(async { fetch_favorites(&get_store(&ctx.env)?).await }).await
+        })
+        .get_async("/api/podium", |req, ctx| async move {
+            json_response(async {
+                let filter = parse_podium_filter(req.url()?.query_pairs())?;
+                get_store(&ctx.env)?
+                    .fetch_podium_data(&filter)
+                    .await
+                    .map_err(AppError::database("Couldn't fetch results from database"))
+            })
+            .await
+        })
+        .get_async("/api/podium/relative", |req, ctx| async move {
+            json_response(async {
+                let filter = parse_podium_filter(req.url()?.query_pairs())?;
+                fetch_relative_podium_data(&filter, &get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .get_async("/api/recent", |_req, ctx| async move {
+            json_response(async { api_recent(&get_store(&ctx.env)?).await }).await
+        })
+        .get_async("/api/puzzles", |_req, ctx| async move {
+            json_response(async { fetch_puzzle_difficulties(&get_store(&ctx.env)?).await }).await
+        })
+        .get_async("/api/seasons", |_req, ctx| async move {
+            json_response(async {
+                let schedule = get_season_schedule(&ctx.env)?;
+                let today = Utc::now().date_naive();
+                fetch_season_summaries(&schedule, today, &get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .get_async("/api/seasons/:id", |_req, ctx| async move {
+            json_response(async {
+                let id = route_param(&ctx, "id")?;
+                let schedule = get_season_schedule(&ctx.env)?;
+                let today = Utc::now().date_naive();
+                fetch_season(&id, &schedule, today, &get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .get_async("/submit", |_req, _ctx| async move {
+            html_response(async { Ok(SubmitTemplate::default().render()?) }).await
+        })
+        .post_async("/submit", |mut req, ctx| async move {
+            match handle_submit(&mut req, &ctx).await {
+                Ok(inserted) => Ok(Response::from_json(&inserted)?.with_status(201)),
+                Err(e) => json_error(&e),
+            }
+        })
+        .post_async("/submit/share", |mut req, ctx| async move {
+            handle_submit_share(&mut req, &ctx).await
+        })
+        .post_async("/rebuild", |req, ctx| async move {
+            let rebuilt = async {
+                authorize_admin(&req, &ctx)?;
+                let schedule = get_season_schedule(&ctx.env)?;
+                let today = Utc::now().date_naive();
+                rebuild_leaderboards(&schedule, today, &get_store(&ctx.env)?).await
+            };
+
+            match rebuilt.await {
+                Ok(()) => Response::ok("Rebuilt leaderboards"),
+                Err(e) => json_error(&e),
+            }
+        })
+        .get_async("/admin", |req, ctx| async move {
+            admin_page(&req, &ctx, "/admin", async {
+                render_admin(&get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .post_async("/admin/login", |mut req, ctx| async move {
+            handle_admin_login(&mut req, &ctx).await
+        })
+        .post_async("/admin/results/edit", |mut req, ctx| async move {
+            handle_correction(&mut req, &ctx, parse_edit_form).await
+        })
+        .post_async("/admin/results/delete", |mut req, ctx| async move {
+            handle_correction(&mut req, &ctx, parse_delete_form).await
+        })
+        .post_async("/admin/users/merge", |mut req, ctx| async move {
+            handle_correction(&mut req, &ctx, parse_merge_form).await
+        })
+        .get_async("/admin/moderation", |req, ctx| async move {
+            admin_page(&req, &ctx, "/admin/moderation", async {
+                render_moderation(&get_store(&ctx.env)?).await
+            })
+            .await
+        })
+        .post_async("/admin/moderation", |mut req, ctx| async move {
+            handle_moderation(&mut req, &ctx).await
         })
         .get_async("/styles/styles.css", |_req, _ctx| async move {
             Response::ok(CSS_STYLES)
         })
+        .or_else_any_method_async("/*path", |req, _ctx| async move {
+            error_page(&AppError::PageNotFound(req.path()))
+        })
         .run(req, env)
         .await
 }
 
-async fn handle_index<T>(ctx: &RouteContext<T>, client: &Postgrest) -> Result<Response> {
-    let db_name = ctx.param("db_name").map_or("all", |str| str).to_string() + "_rust";
+#[event(scheduled)]
+async fn scheduled(_event: ScheduledEvent, env: Env, _ctx: ScheduleContext) {
+    let result = async {
+        let schedule = get_season_schedule(&env)?;
+        rebuild_leaderboards(&schedule, Utc::now().date_naive(), &get_store(&env)?).await
+    };
+
+    if let Err(e) = result.await {
+        console_error!("Couldn't rebuild leaderboards: {e}");
+    }
+}
+
+/// Recomputes the `all_rust`, `last_30_rust` and `last_90_rust` leaderboards, the all-time rating history, the
+/// final standings of every completed season, and the difficulty of every puzzle and score of every result from
+/// every stored result.
+///
+/// Suspicious results are flagged first, and flagged results are left out of ratings until they're approved. The rolling
+/// windows end at the most recent crossword date and start from fresh ratings, as does every season.
+async fn rebuild_leaderboards(
+    schedule: &SeasonSchedule,
+    today: NaiveDate,
+    store: &impl ResultStore,
+) -> AppResult<()> {
+    let results = store
+        .fetch_all_results()
+        .await
+        .map_err(AppError::database("Couldn't fetch results from database"))?;
+
+    if results.is_empty() {
+        return Ok(());
+    }
+
+    let flags = flag_suspicious_results(&results, store).await?;
+    let results = exclude_flagged_results(results, &flags);
+
+    let difficulties = compute_puzzle_difficulties(&results);
+    store
+        .replace_puzzle_difficulties_in_db(&difficulties)
+        .await
+        .map_err(AppError::database(
+            "Couldn't write puzzle difficulties to database",
+        ))?;
+    let scores: Vec<ResultScore> = score_results(&results)
+        .into_iter()
+        .map(|scored| ResultScore {
+            date: scored.result.date,
+            username: scored.result.username,
+            time: scored.result.time,
+            rank: scored.result.rank,
+            field_score: scored.field_score,
+            personal_score: scored.personal_score,
+        })
+        .collect();
+    store
+        .replace_result_scores_in_db(&scores)
+        .await
+        .map_err(AppError::database(
+            "Couldn't write result scores to database",
+        ))?;
+
+    let (leaderboard, history) = compute_leaderboard_with_history(&results, &TrueSkill);
+    store
+        .replace_leaderboard_in_db("all_rust", &leaderboard)
+        .await
+        .map_err(AppError::database("Couldn't write all_rust to database"))?;
+    store
+        .replace_rating_history_in_db(&history)
+        .await
+        .map_err(AppError::database(
+            "Couldn't write rating history to database",
+        ))?;
+
+    for (name, num_days) in LEADERBOARD_WINDOWS {
+        let db_name = format!("{name}_rust");
+        let window_results = filter_to_recent_days(&results, num_days)?;
+
+        store
+            .replace_leaderboard_in_db(&db_name, &compute_leaderboard(&window_results, &TrueSkill))
+            .await
+            .map_err(AppError::database("Couldn't write leaderboard to database"))?;
+    }
+
+    let standings: Vec<SeasonStanding> = list_seasons_from(schedule, &results, today)?
+        .iter()
+        .filter(|season| season_status(season, today) == SeasonStatus::Completed)
+        .flat_map(|season| compute_season_standings(season, &results))
+        .collect();
+    store
+        .replace_season_standings_in_db(&standings)
+        .await
+        .map_err(AppError::database(
+            "Couldn't write season standings to database",
+        ))?;
+
+    Ok(())
+}
+
+/// Flags every suspicious result that hasn't been flagged before.
+///
+/// # Returns
+///
+/// Every flagged result, including the ones flagged by earlier rebuilds.
+async fn flag_suspicious_results(
+    results: &[ResultEntry],
+    store: &impl ResultStore,
+) -> AppResult<Vec<FlaggedResult>> {
+    let mut flags = fetch_flagged_results(store).await?;
+    let new_flags: Vec<FlaggedResult> = detect_suspicious_results(results)
+        .into_iter()
+        .filter(|new_flag| {
+            !flags
+                .iter()
+                .any(|flag| flag.date == new_flag.date && flag.username == new_flag.username)
+        })
+        .collect();
+
+    if !new_flags.is_empty() {
+        store
+            .insert_flagged_results(&new_flags)
+            .await
+            .map_err(AppError::database(
+                "Couldn't write flagged results to database",
+            ))?;
+    }
+    flags.extend(new_flags);
+
+    Ok(flags)
+}
+
+async fn fetch_flagged_results(store: &impl ResultStore) -> AppResult<Vec<FlaggedResult>> {
+    store
+        .fetch_flagged_results()
+        .await
+        .map_err(AppError::database(
+            "Couldn't fetch flagged results from database",
+        ))
+}
+
+/// Fetches every result that counts towards ratings, which is every result except the flagged ones that haven't
+/// been approved.
+async fn fetch_rated_results(store: &impl ResultStore) -> AppResult<Vec<ResultEntry>> {
+    let results = store
+        .fetch_all_results()
+        .await
+        .map_err(AppError::database("Couldn't fetch results from database"))?;
+    let flags = fetch_flagged_results(store).await?;
+
+    Ok(exclude_flagged_results(results, &flags))
+}
+
+/// Fetches a known user's data, leaving out their results with a flag that hasn't been approved.
+async fn fetch_rated_user_data(
+    username: &str,
+    flags: &[FlaggedResult],
+    store: &impl ResultStore,
+) -> AppResult<UserData> {
+    let data = fetch_known_user_data(username, store).await?;
+
+    Ok(build_user_data(exclude_flagged_results(
+        data.all_times,
+        flags,
+    )))
+}
+
+/// Reads the leaderboard window from the `db_name` route parameter and the `from` and `to` query parameters, and
+/// the rating system from the `system` query parameter, defaulting to the all-time TrueSkill leaderboard.
+///
+/// # Returns
+///
+/// A `Result` containing the window and rating system, or a 400 `AppError` describing the invalid parameter.
+fn parse_leaderboard_params<T>(
+    req: &Request,
+    ctx: &RouteContext<T>,
+) -> AppResult<(LeaderboardWindow, RatingSystemKind)> {
+    let url = req.url()?;
+    let query_param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+
+    let window = parse_leaderboard_window(
+        ctx.param("db_name").map_or("all", |str| str),
+        query_param("from").as_deref(),
+        query_param("to").as_deref(),
+    )?;
+
+    let system = match query_param("system") {
+        Some(id) => RatingSystemKind::from_id(&id)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown rating system: {id}")))?,
+        None => RatingSystemKind::default(),
+    };
+
+    Ok((window, system))
+}
+
+/// The table the rebuild writes `window`'s TrueSkill leaderboard to, if it writes one.
+fn stored_leaderboard_table(window: LeaderboardWindow) -> Option<String> {
+    match window {
+        LeaderboardWindow::All => Some(String::from("all_rust")),
+        LeaderboardWindow::LastDays(num_days) => LEADERBOARD_WINDOWS
+            .iter()
+            .find(|(_, window_days)| *window_days == num_days)
+            .map(|(name, _)| format!("{name}_rust")),
+        _ => None,
+    }
+}
+
+/// Loads a leaderboard under the given rating system.
+///
+/// TrueSkill leaderboards for the windows the rebuild writes are read from their tables. Any other window or
+/// rating system is computed on demand from the results in the window.
+async fn load_leaderboard(
+    window: LeaderboardWindow,
+    system: RatingSystemKind,
+    today: NaiveDate,
+    store: &impl ResultStore,
+) -> AppResult<Vec<LeaderboardEntry>> {
+    if system == RatingSystemKind::TrueSkill {
+        if let Some(table) = stored_leaderboard_table(window) {
+            return store
+                .fetch_leaderboard_from_db(&table)
+                .await
+                .map_err(AppError::database(
+                    "Couldn't fetch leaderboard from database",
+                ));
+        }
+    }
+
+    let results = fetch_rated_results(store).await?;
+    let results = filter_to_window(&results, window, today)?;
 
-    let data = fetch_leaderboard_from_db(&db_name, client)
+    Ok(system.compute_leaderboard(&results))
+}
+
+/// Predicts how everyone who solved the most recent crossword will finish in the next one, using their all-time
+/// TrueSkill ratings. Players who aren't on the all-time leaderboard yet are given a new player's rating.
+async fn fetch_favorites(store: &impl ResultStore) -> AppResult<FavoritesResponse> {
+    let date = store
+        .fetch_most_recent_crossword_date()
+        .await
+        .map_err(AppError::database(
+            "Couldn't fetch most recent crossword date from database",
+        ))?
+        .format("%Y-%m-%d")
+        .to_string();
+    let results = store
+        .fetch_results(&date)
         .await
-        .map_err(|e| format!("Couldn't fetch leaderboard from database: {e}"))?;
+        .map_err(AppError::database("Couldn't fetch results from database"))?;
+    let leaderboard =
+        store
+            .fetch_leaderboard_from_db("all_rust")
+            .await
+            .map_err(AppError::database(
+                "Couldn't fetch leaderboard from database",
+            ))?;
 
-    Response::from_html(LeaderboardTemplate { data }.render().unwrap())
+    let ratings: Vec<Rating> = results
+        .iter()
+        .map(|result| {
+            leaderboard
+                .iter()
+                .find(|entry| entry.username == result.username)
+                .map(|entry| Rating {
+                    mu: entry.mu,
+                    sigma: entry.sigma,
+                })
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let mut predictions: Vec<FinishPrediction> = results
+        .into_iter()
+        .zip(&ratings)
+        .zip(predict_finishing_order(&ratings))
+        .map(|((result, rating), rank_probabilities)| FinishPrediction {
+            username: result.username,
+            elo: TrueSkill.elo(rating),
+            win_probability: rank_probabilities[0],
+            expected_rank: rank_probabilities
+                .iter()
+                .enumerate()
+                .map(|(rank, probability)| (rank + 1) as f64 * probability)
+                .sum(),
+            rank_probabilities,
+        })
+        .collect();
+    predictions.sort_by(|a, b| b.win_probability.total_cmp(&a.win_probability));
+
+    Ok(FavoritesResponse { date, predictions })
+}
+
+async fn render_index(
+    window: LeaderboardWindow,
+    system: RatingSystemKind,
+    today: NaiveDate,
+    store: &impl ResultStore,
+) -> AppResult<String> {
+    let data = load_leaderboard(window, system, today, store).await?;
+    // The favorites are an extra, so the leaderboard is still shown if they can't be predicted.
+    let favorites = fetch_favorites(store)
+        .await
+        .map(|mut favorites| {
+            favorites.predictions.truncate(FAVORITES_SHOWN);
+            favorites
+        })
+        .ok();
+
+    let format_date = |date: Option<NaiveDate>| {
+        date.map(|date| date.format("%Y-%m-%d").to_string())
+            .unwrap_or_default()
+    };
+    let (from, to) = match window {
+        LeaderboardWindow::Range(from, to) => (format_date(from), format_date(to)),
+        _ => (String::new(), String::new()),
+    };
+
+    Ok(LeaderboardTemplate {
+        data,
+        system,
+        window_label: describe_leaderboard_window(window),
+        window_links: WINDOW_PRESETS
+            .into_iter()
+            .map(|preset| {
+                (
+                    leaderboard_link(preset, system.id()),
+                    describe_leaderboard_window(preset),
+                    preset == window,
+                )
+            })
+            .collect(),
+        system_links: RatingSystemKind::ALL
+            .into_iter()
+            .map(|option| {
+                (
+                    leaderboard_link(window, option.id()),
+                    option.label(),
+                    option == system,
+                )
+            })
+            .collect(),
+        from,
+        to,
+        favorites,
+    }
+    .render()?)
 }
 
-async fn handle_podium(client: &Postgrest) -> Result<Response> {
-    let podium_data = fetch_podium_data(client)
+/// Fetches the results matching `filter` with the best field scores as of the last rebuild, best first.
+async fn fetch_relative_podium_data(
+    filter: &PodiumFilter,
+    store: &impl ResultStore,
+) -> AppResult<Vec<ScoredResult>> {
+    let scores = store
+        .fetch_relative_podium_data(filter)
         .await
-        .map_err(|e| format!("Couldn't fetch results from database: {e}"))?;
+        .map_err(AppError::database(
+            "Couldn't fetch result scores from database",
+        ))?;
 
-    Response::from_html(PodiumTemplate { data: podium_data }.render().unwrap())
+    Ok(scores
+        .into_iter()
+        .map(|score| ScoredResult {
+            result: ResultEntry {
+                date: score.date,
+                username: score.username,
+                time: score.time,
+                rank: score.rank,
+            },
+            field_score: score.field_score,
+            personal_score: score.personal_score,
+        })
+        .collect())
 }
 
-async fn handle_user<T>(ctx: &RouteContext<T>, client: &Postgrest) -> Result<Response> {
-    let username = match ctx.param("username") {
-        Some(username) => username.replace("%20", " "),
-        None => return Err("Couldn't process username parameter".into()),
+/// Renders the podium, ranked by time or, if `relative` is set, by how far each result beat that day's field.
+async fn render_podium(
+    filter: PodiumFilter,
+    relative: bool,
+    store: &impl ResultStore,
+) -> AppResult<String> {
+    let (podium_data, title) = if relative {
+        (
+            fetch_relative_podium_data(&filter, store).await?,
+            format!("{} Relative to the Field", describe_podium_filter(&filter)),
+        )
+    } else {
+        let podium_data = store
+            .fetch_podium_data(&filter)
+            .await
+            .map_err(AppError::database("Couldn't fetch results from database"))?;
+
+        (
+            podium_data
+                .into_iter()
+                .map(|result| ScoredResult {
+                    result,
+                    field_score: None,
+                    personal_score: None,
+                })
+                .collect(),
+            describe_podium_filter(&filter),
+        )
     };
 
-    let mut data = fetch_user_data(&username, client)
+    Ok(PodiumTemplate {
+        data: podium_data,
+        title,
+        relative,
+        weekday_options: WEEKDAYS
+            .into_iter()
+            .map(|weekday| {
+                (
+                    weekday.to_string().to_lowercase(),
+                    weekday_name(weekday),
+                    filter.weekday == Some(weekday),
+                )
+            })
+            .collect(),
+        month: filter.month.unwrap_or_default() as usize,
+        year: filter.year.map(|year| year.to_string()).unwrap_or_default(),
+        limit: filter.limit,
+    }
+    .render()?)
+}
+
+/// Fetches every result recorded for a user, or `AppError::UserNotFound` if they have none.
+async fn fetch_known_user_data(username: &str, store: &impl ResultStore) -> AppResult<UserData> {
+    let data = store
+        .fetch_user_data(username)
         .await
-        .map_err(|e| format!("Couldn't fetch user data from database: {e}"))?;
+        .map_err(AppError::database("Couldn't fetch user data from database"))?;
+
+    if data.all_times.is_empty() {
+        return Err(AppError::UserNotFound(username.to_string()));
+    }
+
+    Ok(data)
+}
+
+/// Fetches a user's all-time TrueSkill `(mu, sigma)`, or `AppError::UserNotFound` if they aren't rated.
+async fn fetch_known_user_trueskill(
+    username: &str,
+    store: &impl ResultStore,
+) -> AppResult<(f64, f64)> {
+    store
+        .fetch_user_trueskill_from_db(username)
+        .await
+        .map_err(AppError::database("Couldn't fetch trueskill from database"))?
+        .ok_or_else(|| AppError::UserNotFound(username.to_string()))
+}
+
+/// Computes a user's streak and participation statistics up to the most recent crossword date.
+async fn fetch_user_stats(data: &UserData, store: &impl ResultStore) -> AppResult<UserStats> {
+    let most_recent_date =
+        store
+            .fetch_most_recent_crossword_date()
+            .await
+            .map_err(AppError::database(
+                "Couldn't fetch most recent crossword date from database",
+            ))?;
+
+    Ok(compute_user_stats(&data.all_times, most_recent_date))
+}
+
+/// Predicts a user's time for each of the seven days after the most recent crossword date.
+async fn fetch_time_predictions(
+    data: &UserData,
+    store: &impl ResultStore,
+) -> AppResult<Vec<TimePrediction>> {
+    let most_recent_date =
+        store
+            .fetch_most_recent_crossword_date()
+            .await
+            .map_err(AppError::database(
+                "Couldn't fetch most recent crossword date from database",
+            ))?;
+
+    Ok(predict_solve_times(
+        &data.all_times,
+        most_recent_date + Duration::days(1),
+    ))
+}
+
+/// The most recent `RECENT_PREDICTIONS` of a user's past predictions.
+fn recent_prediction_outcomes(outcomes: &[PredictionOutcome]) -> &[PredictionOutcome] {
+    &outcomes[outcomes.len().saturating_sub(RECENT_PREDICTIONS)..]
+}
+
+/// Fetches a user's results with the best field scores as of the last rebuild, best first.
+///
+/// # Arguments
+///
+/// * `username` - The user to fetch results for.
+/// * `results` - Every result recorded for the user.
+/// * `store` - The store to read the scores from.
+async fn fetch_best_relative_results(
+    username: &str,
+    results: &[ResultEntry],
+    store: &impl ResultStore,
+) -> AppResult<Vec<ScoredResult>> {
+    let scores = store
+        .fetch_best_result_scores(username, BEST_RELATIVE_RESULTS_SHOWN)
+        .await
+        .map_err(AppError::database(
+            "Couldn't fetch result scores from database",
+        ))?;
+
+    Ok(scores
+        .into_iter()
+        .filter_map(|score| {
+            let result = results.iter().find(|result| result.date == score.date)?;
+            Some(ScoredResult {
+                result: result.clone(),
+                field_score: score.field_score,
+                personal_score: score.personal_score,
+            })
+        })
+        .collect())
+}
+
+async fn render_user(username: String, store: &impl ResultStore) -> AppResult<String> {
+    let mut data = fetch_known_user_data(&username, store).await?;
+    let stats = fetch_user_stats(&data, store).await?;
+    let best_relative_results =
+        fetch_best_relative_results(&username, &data.all_times, store).await?;
 
     let scatter_plot_html = generate_scatter_plot_html(vec![&mut data.times_excluding_saturday])
         .unwrap_or_else(|_| String::from("Need more times before we can plot!"));
@@ -98,72 +1090,302 @@ async fn handle_user<T>(ctx: &RouteContext<T>, client: &Postgrest) -> Result<Res
     let box_plot_html = generate_box_plot_html(vec![&mut data.times_excluding_saturday])
         .unwrap_or_else(|_| String::from("Need more times before we can plot!"));
 
-    Response::from_html(
-        UserTemplate {
-            username,
-            scatter_plot_html,
-            box_plot_html,
-            top_times: data.all_times.get(..3).unwrap_or(&data.all_times).to_vec(),
-        }
-        .render()
-        .unwrap(),
-    )
+    let rating_history =
+        store
+            .fetch_rating_history(&username)
+            .await
+            .map_err(AppError::database(
+                "Couldn't fetch rating history from database",
+            ))?;
+
+    let elo_plot_html = generate_elo_plot_html(&rating_history)
+        .unwrap_or_else(|_| String::from("Need more crosswords before we can plot!"));
+
+    let weekday_plot_html = generate_weekday_box_plot_html(&data.times_by_weekday)
+        .unwrap_or_else(|_| String::from("Need more times before we can plot!"));
+
+    let predictions = fetch_time_predictions(&data, store).await?;
+    let prediction_history = compute_prediction_outcomes(&data.all_times);
+    let prediction_plot_html = generate_prediction_plot_html(&prediction_history)
+        .unwrap_or_else(|_| String::from("Need more times before we can plot!"));
+
+    Ok(UserTemplate {
+        username,
+        scatter_plot_html,
+        box_plot_html,
+        elo_plot_html,
+        top_times: data.all_times.get(..3).unwrap_or(&data.all_times).to_vec(),
+        stats,
+        weekday_stats: compute_weekday_stats(&data),
+        weekday_plot_html,
+        best_relative_results,
+        predictions,
+        prediction_accuracy: summarize_prediction_accuracy(&prediction_history),
+        recent_prediction_accuracy: summarize_prediction_accuracy(recent_prediction_outcomes(
+            &prediction_history,
+        )),
+        prediction_plot_html,
+    }
+    .render()?)
 }
 
-async fn handle_history<T>(ctx: &RouteContext<T>, client: &Postgrest) -> Result<Response> {
-    let date = ctx
-        .param("date")
-        .ok_or("Couldn't process date parameter")?
-        .to_string();
-    let data = fetch_results(&date, client)
-        .await
-        .map_err(|e| format!("Couldn't fetch results from database: {e}"))?;
+/// Fetches the results for a date, or `AppError::InvalidDate` if `date` isn't a YYYY-MM-DD date.
+async fn fetch_history(date: &str, store: &impl ResultStore) -> AppResult<Vec<ResultEntry>> {
+    if NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+        return Err(AppError::InvalidDate(date.to_string()));
+    }
 
-    Response::from_html(HistoryTemplate { date, data }.render().unwrap())
+    store
+        .fetch_results(date)
+        .await
+        .map_err(AppError::database("Couldn't fetch results from database"))
 }
 
-async fn handle_recent(client: &Postgrest) -> Result<Response> {
-    let most_recent_date = fetch_most_recent_crossword_date(client)
+async fn render_history(date: String, store: &impl ResultStore) -> AppResult<String> {
+    let results = fetch_history(&date, store).await?;
+    let difficulty = fetch_puzzle_difficulty(&date, store).await?;
+
+    let scores = store
+        .fetch_result_scores(&date)
         .await
-        .map_err(|e| format!("Couldn't fetch most recent crossword date from database: {e}"))?;
+        .map_err(AppError::database(
+            "Couldn't fetch result scores from database",
+        ))?;
+    let data = results
+        .into_iter()
+        .map(|result| {
+            let score = scores
+                .iter()
+                .find(|score| score.username == result.username);
+            ScoredResult {
+                field_score: score.and_then(|score| score.field_score),
+                personal_score: score.and_then(|score| score.personal_score),
+                result,
+            }
+        })
+        .collect();
+
+    Ok(HistoryTemplate {
+        date,
+        data,
+        difficulty,
+    }
+    .render()?)
+}
+
+/// Lists the 10 dates up to and including the most recent crossword date, most recent first.
+async fn recent_dates(store: &impl ResultStore) -> AppResult<Vec<String>> {
+    let most_recent_date =
+        store
+            .fetch_most_recent_crossword_date()
+            .await
+            .map_err(AppError::database(
+                "Couldn't fetch most recent crossword date from database",
+            ))?;
 
-    let dates: Vec<String> = (0..10)
+    Ok((0..10)
         .map(|i| {
             (most_recent_date - Duration::days(i))
                 .format("%Y-%m-%d")
                 .to_string()
         })
+        .collect())
+}
+
+async fn render_recent(store: &impl ResultStore) -> AppResult<String> {
+    let dates = recent_dates(store).await?;
+    let mut difficulties: HashMap<String, PuzzleDifficulty> = store
+        .fetch_puzzle_difficulties_since(&dates[dates.len() - 1])
+        .await
+        .map_err(AppError::database(
+            "Couldn't fetch puzzle difficulties from database",
+        ))?
+        .into_iter()
+        .map(|difficulty| (difficulty.date.clone(), difficulty))
+        .collect();
+    let puzzles = dates
+        .into_iter()
+        .map(|date| {
+            let difficulty = difficulties.remove(&date);
+            (date, difficulty)
+        })
         .collect();
 
-    Response::from_html(RecentTemplate { dates }.render().unwrap())
+    Ok(RecentTemplate { puzzles }.render()?)
 }
 
-async fn handle_h2h<T>(ctx: &RouteContext<T>, client: &Postgrest) -> Result<Response> {
-    let users = fetch_usernames_sorted_by_elo(client)
+/// Fetches the difficulty of every puzzle as of the last rebuild, sorted from hardest to easiest.
+async fn fetch_puzzle_difficulties(store: &impl ResultStore) -> AppResult<Vec<PuzzleDifficulty>> {
+    store
+        .fetch_puzzle_difficulties()
         .await
-        .map_err(|e| format!("Couldn't fetch usernames from database: {e}"))?;
+        .map_err(AppError::database(
+            "Couldn't fetch puzzle difficulties from database",
+        ))
+}
 
-    let (user1, user2) = match (ctx.param("user1"), ctx.param("user2")) {
-        (Some(u1), Some(u2)) => (u1.replace("%20", " "), u2.replace("%20", " ")),
-        _ => {
-            return Response::from_html(
-                HeadToHeadTemplate {
-                    users,
-                    ..Default::default()
-                }
-                .render()
-                .unwrap(),
-            )
-        }
+/// Fetches the difficulty of the puzzle for a date as of the last rebuild.
+async fn fetch_puzzle_difficulty(
+    date: &str,
+    store: &impl ResultStore,
+) -> AppResult<Option<PuzzleDifficulty>> {
+    store
+        .fetch_puzzle_difficulty(date)
+        .await
+        .map_err(AppError::database(
+            "Couldn't fetch puzzle difficulty from database",
+        ))
+}
+
+async fn render_puzzles(store: &impl ResultStore) -> AppResult<String> {
+    let difficulties = fetch_puzzle_difficulties(store).await?;
+
+    Ok(PuzzlesTemplate {
+        hardest: difficulties.iter().take(PUZZLES_SHOWN).cloned().collect(),
+        easiest: difficulties
+            .iter()
+            .rev()
+            .take(PUZZLES_SHOWN)
+            .cloned()
+            .collect(),
+    }
+    .render()?)
+}
+
+/// Lists the seasons in `schedule`, starting from the season of the first crossword in `results`.
+fn list_seasons_from(
+    schedule: &SeasonSchedule,
+    results: &[ResultEntry],
+    today: NaiveDate,
+) -> AppResult<Vec<Season>> {
+    let first_date = match results.iter().map(|entry| entry.date.as_str()).min() {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(AppError::database("Couldn't parse crossword date"))?,
+        None => today,
     };
 
-    let mut user1_data = fetch_user_data(&user1, client)
+    Ok(list_seasons(schedule, first_date, today)?)
+}
+
+/// Lists every season with its top three, most recent first.
+///
+/// Completed seasons use the standings archived by the rebuild. Seasons without archived standings, like the
+/// current one, are ranked from their results so far.
+async fn fetch_season_summaries(
+    schedule: &SeasonSchedule,
+    today: NaiveDate,
+    store: &impl ResultStore,
+) -> AppResult<Vec<SeasonResults>> {
+    let results = fetch_rated_results(store).await?;
+    let archived = store
+        .fetch_season_podiums()
         .await
-        .map_err(|e| format!("Couldn't fetch user1 data from database: {e}"))?;
+        .map_err(AppError::database(
+            "Couldn't fetch season standings from database",
+        ))?;
+
+    Ok(list_seasons_from(schedule, &results, today)?
+        .into_iter()
+        .map(|season| {
+            let mut standings: Vec<SeasonStanding> = archived
+                .iter()
+                .filter(|standing| standing.season_id == season.id)
+                .cloned()
+                .collect();
+            if standings.is_empty() {
+                standings = compute_season_standings(&season, &results);
+                standings.truncate(3);
+            }
+
+            SeasonResults {
+                status: season_status(&season, today),
+                season,
+                standings,
+            }
+        })
+        .collect())
+}
+
+/// Fetches a season's full standings, or `AppError::SeasonNotFound` if `schedule` has no season with the id.
+///
+/// Completed seasons use the standings archived by the rebuild, falling back to ranking the season's results if
+/// it hasn't been archived yet.
+async fn fetch_season(
+    id: &str,
+    schedule: &SeasonSchedule,
+    today: NaiveDate,
+    store: &impl ResultStore,
+) -> AppResult<SeasonResults> {
+    let results = fetch_rated_results(store).await?;
+    let season = list_seasons_from(schedule, &results, today)?
+        .into_iter()
+        .find(|season| season.id == id)
+        .ok_or_else(|| AppError::SeasonNotFound(id.to_string()))?;
+
+    let mut standings =
+        store
+            .fetch_season_standings(&season.id)
+            .await
+            .map_err(AppError::database(
+                "Couldn't fetch season standings from database",
+            ))?;
+    if standings.is_empty() {
+        standings = compute_season_standings(&season, &results);
+    }
 
-    let mut user2_data = fetch_user_data(&user2, client)
+    Ok(SeasonResults {
+        status: season_status(&season, today),
+        season,
+        standings,
+    })
+}
+
+async fn render_seasons(
+    schedule: &SeasonSchedule,
+    today: NaiveDate,
+    store: &impl ResultStore,
+) -> AppResult<String> {
+    let seasons = fetch_season_summaries(schedule, today, store).await?;
+
+    Ok(SeasonsTemplate { seasons }.render()?)
+}
+
+async fn render_season(
+    id: &str,
+    schedule: &SeasonSchedule,
+    today: NaiveDate,
+    store: &impl ResultStore,
+) -> AppResult<String> {
+    let season = fetch_season(id, schedule, today, store).await?;
+
+    Ok(SeasonTemplate { season }.render()?)
+}
+
+async fn render_h2h(
+    users: Option<(String, String)>,
+    store: &impl ResultStore,
+) -> AppResult<String> {
+    let usernames = store
+        .fetch_usernames_sorted_by_elo()
         .await
-        .map_err(|e| format!("Couldn't fetch user2 data from database: {e}"))?;
+        .map_err(AppError::database("Couldn't fetch usernames from database"))?;
+
+    let Some((user1, user2)) = users else {
+        return Ok(HeadToHeadTemplate {
+            users: usernames,
+            ..Default::default()
+        }
+        .render()?);
+    };
+
+    let flags = fetch_flagged_results(store).await?;
+    let mut user1_data = fetch_rated_user_data(&user1, &flags, store).await?;
+    let mut user2_data = fetch_rated_user_data(&user2, &flags, store).await?;
+
+    let matches = compute_h2h_matches(&user1, &user1_data.all_times, &user2, &user2_data.all_times);
+    let data = compute_h2h_stats(&user1, &user2, &matches);
+    let h2h_plot_html = generate_h2h_plot_html(&matches)
+        .unwrap_or_else(|_| String::from("Need shared crosswords before we can plot!"));
 
     let box_plot_html =
         generate_box_plot_html(vec![&mut user1_data.all_times, &mut user2_data.all_times])
@@ -175,27 +1397,1779 @@ async fn handle_h2h<T>(ctx: &RouteContext<T>, client: &Postgrest) -> Result<Resp
                 String::from("Need more times before we can generate scatter plot!")
             });
 
-    let (user1_mu, user1_sigma) = fetch_user_trueskill_from_db(&user1, client)
+    let user1_rating = fetch_known_user_trueskill(&user1, store).await?;
+    let user2_rating = fetch_known_user_trueskill(&user2, store).await?;
+
+    let win_probability = compute_win_probability(user1_rating, user2_rating);
+
+    Ok(HeadToHeadTemplate {
+        users: usernames,
+        data,
+        box_plot_html,
+        scatter_plot_html,
+        win_probability,
+        matches,
+        h2h_plot_html,
+    }
+    .render()?)
+}
+
+/// Reads the number of players to include in the head-to-head matrix from the `top` query parameter.
+///
+/// # Returns
+///
+/// A `Result` containing the number of players, `None` for every rated player, or a 400 `AppError` if `top`
+/// isn't a positive number.
+fn parse_matrix_size(req: &Request) -> AppResult<Option<usize>> {
+    let url = req.url()?;
+    let top = url
+        .query_pairs()
+        .find(|(key, _)| key == "top")
+        .map(|(_, value)| value.trim().to_string())
+        .unwrap_or_default();
+    if top.is_empty() {
+        return Ok(None);
+    }
+
+    match top.parse() {
+        Ok(top) if top > 0 => Ok(Some(top)),
+        _ => Err(AppError::BadRequest(format!(
+            "top must be a positive number, not {top}"
+        ))),
+    }
+}
+
+/// Computes the head-to-head records between the `top` highest-rated players, or between every rated player if
+/// `top` is `None`, ordered by ELO.
+async fn fetch_h2h_matrix(
+    top: Option<usize>,
+    store: &impl ResultStore,
+) -> AppResult<HeadToHeadMatrix> {
+    let mut usernames = store
+        .fetch_usernames_sorted_by_elo()
         .await
-        .map_err(|e| format!("Couldn't fetch user1 trueskill from database: {e}"))?;
+        .map_err(AppError::database("Couldn't fetch usernames from database"))?;
+    if let Some(top) = top {
+        usernames.truncate(top);
+    }
+
+    let results = fetch_rated_results(store).await?;
+
+    Ok(compute_h2h_matrix(&results, usernames))
+}
+
+async fn render_h2h_matrix(top: Option<usize>, store: &impl ResultStore) -> AppResult<String> {
+    let matrix = fetch_h2h_matrix(top, store).await?;
+
+    Ok(HeadToHeadMatrixTemplate {
+        matrix,
+        top: top.map(|top| top.to_string()).unwrap_or_default(),
+    }
+    .render()?)
+}
+
+/// Reads the players to compare from the `users` query parameters.
+fn parse_compare_params(req: &Request) -> AppResult<Vec<String>> {
+    let url = req.url()?;
+
+    Ok(parse_compared_users(
+        url.query_pairs()
+            .filter(|(key, _)| key == "users")
+            .map(|(_, value)| value),
+    )?)
+}
+
+/// Fetches the all-time stats, results and pairwise win probabilities of the players being compared, in the
+/// order given. Players who aren't on the all-time leaderboard produce `AppError::UserNotFound`.
+async fn fetch_comparison(
+    usernames: &[String],
+    store: &impl ResultStore,
+) -> AppResult<(ComparisonResponse, Vec<UserData>)> {
+    let leaderboard =
+        store
+            .fetch_leaderboard_from_db("all_rust")
+            .await
+            .map_err(AppError::database(
+                "Couldn't fetch leaderboard from database",
+            ))?;
+
+    let mut players = Vec::new();
+    let mut user_data = Vec::new();
+    for username in usernames {
+        let data = fetch_known_user_data(username, store).await?;
+        let player = leaderboard
+            .iter()
+            .find(|entry| entry.username == *username)
+            .and_then(|entry| summarize_compared_player(entry.clone(), &data.all_times))
+            .ok_or_else(|| AppError::UserNotFound(username.clone()))?;
+
+        players.push(player);
+        user_data.push(data);
+    }
+
+    let ratings: Vec<(f64, f64)> = players
+        .iter()
+        .map(|player| (player.entry.mu, player.entry.sigma))
+        .collect();
+
+    Ok((
+        ComparisonResponse {
+            win_probabilities: compute_win_probability_matrix(&ratings),
+            players,
+        },
+        user_data,
+    ))
+}
 
-    let (user2_mu, user2_sigma) = fetch_user_trueskill_from_db(&user2, client)
+async fn render_compare(usernames: Vec<String>, store: &impl ResultStore) -> AppResult<String> {
+    let user_options = store
+        .fetch_usernames_sorted_by_elo()
         .await
-        .map_err(|e| format!("Couldn't fetch user2 trueskill from database: {e}"))?;
+        .map_err(AppError::database("Couldn't fetch usernames from database"))?
+        .into_iter()
+        .map(|username| {
+            let selected = usernames.contains(&username);
+            (username, selected)
+        })
+        .collect();
+
+    if usernames.is_empty() {
+        return Ok(CompareTemplate {
+            user_options,
+            ..Default::default()
+        }
+        .render()?);
+    }
+
+    let (comparison, mut user_data) = fetch_comparison(&usernames, store).await?;
+
+    let scatter_plot_html = generate_scatter_plot_html(
+        user_data
+            .iter_mut()
+            .map(|data| data.all_times.as_mut_slice())
+            .collect(),
+    )
+    .unwrap_or_else(|_| String::from("Need more times before we can generate scatter plot!"));
 
-    let win_probability = compute_win_probability((user1_mu, user1_sigma), (user2_mu, user2_sigma));
+    let box_plot_html = generate_box_plot_html(
+        user_data
+            .iter_mut()
+            .map(|data| data.all_times.as_mut_slice())
+            .collect(),
+    )
+    .unwrap_or_else(|_| String::from("Need more times before we can generate box plot!"));
+
+    Ok(CompareTemplate {
+        user_options,
+        comparison: Some(comparison),
+        scatter_plot_html,
+        box_plot_html,
+    }
+    .render()?)
+}
+
+async fn api_compare(
+    usernames: Vec<String>,
+    store: &impl ResultStore,
+) -> AppResult<ComparisonResponse> {
+    if usernames.is_empty() {
+        return Err(ComparisonError::TooFewUsers.into());
+    }
 
-    let data = fetch_h2h_data(user1, user2, client).await.ok();
+    Ok(fetch_comparison(&usernames, store).await?.0)
+}
+
+async fn api_user(username: String, store: &impl ResultStore) -> AppResult<UserResponse> {
+    let data = fetch_known_user_data(&username, store).await?;
+    let stats = fetch_user_stats(&data, store).await?;
+    let best_relative_results =
+        fetch_best_relative_results(&username, &data.all_times, store).await?;
 
-    Response::from_html(
-        HeadToHeadTemplate {
-            users,
-            data,
-            box_plot_html,
-            scatter_plot_html,
-            win_probability,
+    let rating_history =
+        store
+            .fetch_rating_history(&username)
+            .await
+            .map_err(AppError::database(
+                "Couldn't fetch rating history from database",
+            ))?;
+    let predictions = fetch_time_predictions(&data, store).await?;
+    let prediction_history = compute_prediction_outcomes(&data.all_times);
+
+    Ok(UserResponse {
+        username,
+        stats,
+        weekday_stats: compute_weekday_stats(&data),
+        best_relative_results,
+        results: data.all_times,
+        rating_history,
+        predictions,
+        prediction_accuracy: summarize_prediction_accuracy(&prediction_history),
+        recent_prediction_accuracy: summarize_prediction_accuracy(recent_prediction_outcomes(
+            &prediction_history,
+        )),
+        prediction_history,
+    })
+}
+
+async fn api_h2h(
+    user1: String,
+    user2: String,
+    store: &impl ResultStore,
+) -> AppResult<HeadToHeadResponse> {
+    let user1_rating = fetch_known_user_trueskill(&user1, store).await?;
+    let user2_rating = fetch_known_user_trueskill(&user2, store).await?;
+
+    let flags = fetch_flagged_results(store).await?;
+    let user1_data = fetch_rated_user_data(&user1, &flags, store).await?;
+    let user2_data = fetch_rated_user_data(&user2, &flags, store).await?;
+    let matches = compute_h2h_matches(&user1, &user1_data.all_times, &user2, &user2_data.all_times);
+    let stats = compute_h2h_stats(&user1, &user2, &matches);
+
+    Ok(HeadToHeadResponse {
+        user1,
+        user2,
+        win_probability: compute_win_probability(user1_rating, user2_rating),
+        stats,
+        matches,
+    })
+}
+
+async fn api_recent(store: &impl ResultStore) -> AppResult<Vec<DailyResults>> {
+    let dates = recent_dates(store).await?;
+    let mut results_by_date: HashMap<String, Vec<ResultEntry>> = HashMap::new();
+    for entry in store
+        .fetch_results_since(&dates[dates.len() - 1])
+        .await
+        .map_err(AppError::database("Couldn't fetch results from database"))?
+    {
+        results_by_date
+            .entry(entry.date.clone())
+            .or_default()
+            .push(entry);
+    }
+
+    Ok(dates
+        .into_iter()
+        .map(|date| DailyResults {
+            results: results_by_date.remove(&date).unwrap_or_default(),
+            date,
+        })
+        .collect())
+}
+
+/// Validates manual submissions and inserts them, one date at a time.
+///
+/// # Arguments
+///
+/// * `submissions` - The submissions to insert.
+/// * `today` - Today's date, used to reject results for future puzzles.
+/// * `store` - The store to insert the results into.
+///
+/// # Returns
+///
+/// A `Result` containing the inserted `ResultEntry` structs with their ranks, or an error. Invalid or duplicate
+/// submissions produce `AppError::Submission`, and every date is checked for duplicates before any of them are
+/// inserted.
+async fn submit_results(
+    submissions: Vec<Submission>,
+    today: NaiveDate,
+    store: &impl ResultStore,
+) -> AppResult<Vec<ResultEntry>> {
+    let mut entries_by_date: Vec<(String, Vec<ResultEntry>)> = Vec::new();
+    for submission in submissions {
+        let entry = validate_submission(submission, today)?;
+
+        match entries_by_date
+            .iter_mut()
+            .find(|(date, _)| *date == entry.date)
+        {
+            Some((_, entries)) => entries.push(entry),
+            None => entries_by_date.push((entry.date.clone(), vec![entry])),
         }
-        .render()
-        .unwrap(),
+    }
+
+    for (date, entries) in &entries_by_date {
+        let existing_entries = store
+            .fetch_results(date)
+            .await
+            .map_err(AppError::database("Couldn't fetch results from database"))?;
+        merge_new_results(date, &existing_entries, entries.clone())?;
+    }
+
+    let mut submitted = Vec::new();
+    for (date, entries) in entries_by_date {
+        submitted.extend(store.insert_results(&date, entries).await?);
+    }
+
+    Ok(submitted)
+}
+
+async fn handle_submit<T>(req: &mut Request, ctx: &RouteContext<T>) -> AppResult<Vec<ResultEntry>> {
+    if !is_authorized(req, ctx)? {
+        return Err(AppError::Unauthorized);
+    }
+
+    let submission: Submission = req
+        .json()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Couldn't parse submission: {e}")))?;
+
+    submit_results(
+        vec![submission],
+        Utc::now().date_naive(),
+        &get_store(&ctx.env)?,
+    )
+    .await
+}
+
+async fn handle_submit_share<T>(req: &mut Request, ctx: &RouteContext<T>) -> Result<Response> {
+    let form = req.form_data().await?;
+    let username = form.get_field("username").unwrap_or_default();
+    let text = form.get_field("text").unwrap_or_default();
+    let secret = form.get_field("secret").unwrap_or_default();
+
+    let submitted = async {
+        if !is_valid_secret(&secret, ctx)? {
+            return Err(AppError::Unauthorized);
+        }
+
+        let submissions = parse_share_text(&text, Some(&username))?
+            .into_iter()
+            .map(|entry| Submission {
+                date: entry.date,
+                username: entry.username,
+                time: entry.time,
+            })
+            .collect();
+
+        submit_results(submissions, Utc::now().date_naive(), &get_store(&ctx.env)?).await
+    };
+
+    let (template, status) = match submitted.await {
+        Ok(submitted) => (
+            SubmitTemplate {
+                submitted,
+                ..Default::default()
+            },
+            200,
+        ),
+        Err(e) if e.status() < 500 => (
+            SubmitTemplate {
+                username,
+                text,
+                error: Some(e.to_string()),
+                ..Default::default()
+            },
+            e.status(),
+        ),
+        Err(e) => return error_page(&e),
+    };
+
+    let html = template.render().map_err(|e| e.to_string())?;
+
+    Ok(Response::from_html(html)?.with_status(status))
+}
+
+async fn render_admin(store: &impl ResultStore) -> AppResult<String> {
+    Ok(AdminTemplate {
+        audit_log: fetch_audit_log(store).await?,
+        ..Default::default()
+    }
+    .render()?)
+}
+
+/// Fetches the most recent `AUDIT_LOG_SHOWN` audit log entries, most recent first.
+async fn fetch_audit_log(store: &impl ResultStore) -> AppResult<Vec<AuditEntry>> {
+    let mut audit_log = store
+        .fetch_audit_log()
+        .await
+        .map_err(AppError::database("Couldn't fetch audit log from database"))?;
+    audit_log.truncate(AUDIT_LOG_SHOWN);
+
+    Ok(audit_log)
+}
+
+/// Records an admin change in the audit log and rebuilds the leaderboards so ratings reflect it straight away.
+///
+/// The change has already been made by the time this runs, so failures are logged and turned into a warning for the
+/// admin instead of an error.
+///
+/// # Returns
+///
+/// The audit log entry for the change, and a warning if it couldn't be recorded or the rebuild failed.
+async fn finish_admin_change(
+    action: AuditAction,
+    description: String,
+    note: &str,
+    now: DateTime<Utc>,
+    schedule: &SeasonSchedule,
+    store: &impl ResultStore,
+) -> (AuditEntry, Option<String>) {
+    let entry = AuditEntry {
+        timestamp: now.to_rfc3339(),
+        action,
+        description,
+        note: note.trim().to_string(),
+    };
+
+    let mut failures = Vec::new();
+    if let Err(e) = store.insert_audit_entry(&entry).await {
+        console_error!("Couldn't write audit log to database: {e}");
+        failures.push("it couldn't be recorded in the audit log");
+    }
+    if let Err(e) = rebuild_leaderboards(schedule, now.date_naive(), store).await {
+        console_error!("Couldn't rebuild leaderboards: {e}");
+        failures.push("the leaderboards won't reflect it until the next scheduled rebuild");
+    }
+    let warning = (!failures.is_empty())
+        .then(|| format!("The change was applied, but {}.", failures.join(" and ")));
+
+    (entry, warning)
+}
+
+/// Fetches a user's result for a date, or returns `AppError::ResultNotFound` if they don't have one.
+async fn fetch_existing_result(
+    date: &str,
+    username: &str,
+    store: &impl ResultStore,
+) -> AppResult<ResultEntry> {
+    store
+        .fetch_results(date)
+        .await
+        .map_err(AppError::database("Couldn't fetch results from database"))?
+        .into_iter()
+        .find(|entry| entry.username == username)
+        .ok_or_else(|| AppError::ResultNotFound(date.to_string(), username.to_string()))
+}
+
+/// Applies an admin's correction to the stored results, records it in the audit log and rebuilds the leaderboards
+/// so ratings reflect it straight away.
+///
+/// Editing or deleting a result also removes its flag, so an edited time is checked again from scratch.
+///
+/// # Arguments
+///
+/// * `correction` - The change to make.
+/// * `note` - Why the admin made the change, which is kept in the audit log.
+/// * `now` - The current time, used to timestamp the audit log entry.
+/// * `schedule` - The season schedule to rebuild the season standings with.
+/// * `store` - The store to correct.
+///
+/// # Returns
+///
+/// A `Result` containing the audit log entry for the change and a warning if it was applied but couldn't be recorded
+/// or the rebuild failed, or an error if it wasn't applied. Corrections to a result that doesn't exist produce
+/// `AppError::ResultNotFound`, and merges of users who both played the same crossword produce `AppError::Correction`.
+async fn apply_correction(
+    correction: Correction,
+    note: &str,
+    now: DateTime<Utc>,
+    schedule: &SeasonSchedule,
+    store: &impl ResultStore,
+) -> AppResult<(AuditEntry, Option<String>)> {
+    let (action, description) = match correction {
+        Correction::EditTime {
+            date,
+            username,
+            time,
+        } => {
+            let corrected = validate_submission(
+                Submission {
+                    date,
+                    username,
+                    time,
+                },
+                now.date_naive(),
+            )?;
+            let existing =
+                fetch_existing_result(&corrected.date, &corrected.username, store).await?;
+
+            store
+                .update_result_time(&corrected.date, &corrected.username, corrected.time)
+                .await
+                .map_err(AppError::database("Couldn't update result in database"))?;
+            // The old time's flag and moderation decision no longer apply, so the rebuild checks the new one.
+            store
+                .delete_flagged_result(&corrected.date, &corrected.username)
+                .await
+                .map_err(AppError::database(
+                    "Couldn't delete flagged result from database",
+                ))?;
+
+            (
+                AuditAction::EditTime,
+                format!(
+                    "Changed {}'s time for {} from {} to {} seconds",
+                    corrected.username, corrected.date, existing.time, corrected.time
+                ),
+            )
+        }
+        Correction::Delete { date, username } => {
+            let existing = fetch_existing_result(&date, &username, store).await?;
+
+            store
+                .delete_result(&date, &username)
+                .await
+                .map_err(AppError::database("Couldn't delete result from database"))?;
+            store
+                .delete_flagged_result(&date, &username)
+                .await
+                .map_err(AppError::database(
+                    "Couldn't delete flagged result from database",
+                ))?;
+
+            (
+                AuditAction::Delete,
+                format!(
+                    "Deleted {username}'s time of {} seconds for {date}",
+                    existing.time
+                ),
+            )
+        }
+        Correction::MergeUsers { from, to } => {
+            let from_data = fetch_known_user_data(&from, store).await?;
+            let to_data = store
+                .fetch_user_data(&to)
+                .await
+                .map_err(AppError::database("Couldn't fetch user data from database"))?;
+            validate_merge(&from, &to, &from_data.all_times, &to_data.all_times)?;
+
+            store
+                .rename_user(&from, &to)
+                .await
+                .map_err(AppError::database("Couldn't merge users in database"))?;
+
+            (
+                AuditAction::MergeUsers,
+                format!(
+                    "Merged {from} into {to}, moving {} results",
+                    from_data.all_times.len()
+                ),
+            )
+        }
+    };
+
+    Ok(finish_admin_change(action, description, note, now, schedule, store).await)
+}
+
+/// Reads a form field, or returns `AppError::BadRequest` if it's missing or blank.
+fn required_field(form: &FormData, name: &str) -> AppResult<String> {
+    form.get_field(name)
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| AppError::BadRequest(format!("Missing {name}")))
+}
+
+fn parse_edit_form(form: &FormData) -> AppResult<Correction> {
+    Ok(Correction::EditTime {
+        date: required_field(form, "date")?,
+        username: required_field(form, "username")?,
+        time: parse_corrected_time(&required_field(form, "time")?)?,
+    })
+}
+
+fn parse_delete_form(form: &FormData) -> AppResult<Correction> {
+    Ok(Correction::Delete {
+        date: required_field(form, "date")?,
+        username: required_field(form, "username")?,
+    })
+}
+
+fn parse_merge_form(form: &FormData) -> AppResult<Correction> {
+    Ok(Correction::MergeUsers {
+        from: required_field(form, "from")?,
+        to: required_field(form, "to")?,
+    })
+}
+
+async fn handle_correction<T>(
+    req: &mut Request,
+    ctx: &RouteContext<T>,
+    parse_form: fn(&FormData) -> AppResult<Correction>,
+) -> Result<Response> {
+    match authorize_admin(req, ctx) {
+        Ok(()) => {}
+        Err(e @ AppError::Unauthorized) => return admin_login_page("/admin", Some(e.to_string())),
+        Err(e) => return error_page(&e),
+    }
+
+    let form = req.form_data().await?;
+    let note = form.get_field("note").unwrap_or_default();
+
+    let store = match get_store(&ctx.env) {
+        Ok(store) => store,
+        Err(e) => return error_page(&e),
+    };
+    let corrected = async {
+        let correction = parse_form(&form)?;
+        let schedule = get_season_schedule(&ctx.env)?;
+        apply_correction(correction, &note, Utc::now(), &schedule, &store).await
+    };
+
+    let (mut template, status) = match corrected.await {
+        Ok((entry, warning)) => (
+            AdminTemplate {
+                message: Some(entry.description),
+                warning,
+                ..Default::default()
+            },
+            200,
+        ),
+        Err(e) if e.status() < 500 => (
+            AdminTemplate {
+                error: Some(e.to_string()),
+                ..Default::default()
+            },
+            e.status(),
+        ),
+        Err(e) => return error_page(&e),
+    };
+
+    template.audit_log = match fetch_audit_log(&store).await {
+        Ok(audit_log) => audit_log,
+        Err(e) => return error_page(&e),
+    };
+    let html = template.render().map_err(|e| e.to_string())?;
+
+    Ok(Response::from_html(html)?.with_status(status))
+}
+
+async fn render_moderation(store: &impl ResultStore) -> AppResult<String> {
+    Ok(ModerationTemplate {
+        flags: fetch_flagged_results(store).await?,
+        ..Default::default()
+    }
+    .render()?)
+}
+
+/// Describes setting a flag's moderation status, e.g. "Approved".
+fn moderation_verb(status: ModerationStatus) -> &'static str {
+    match status {
+        ModerationStatus::Approved => "Approved",
+        ModerationStatus::Rejected => "Rejected",
+        ModerationStatus::Pending => "Reopened",
+    }
+}
+
+/// Sets the moderation status of a flagged result, records the decision in the audit log and rebuilds the
+/// leaderboards so ratings reflect it straight away, or returns `AppError::FlagNotFound` if it wasn't flagged.
+///
+/// # Returns
+///
+/// The updated flag, and a warning if the decision was saved but couldn't be recorded or the rebuild failed.
+async fn moderate_result(
+    date: &str,
+    username: &str,
+    status: ModerationStatus,
+    now: DateTime<Utc>,
+    schedule: &SeasonSchedule,
+    store: &impl ResultStore,
+) -> AppResult<(FlaggedResult, Option<String>)> {
+    let flag = fetch_flagged_results(store)
+        .await?
+        .into_iter()
+        .find(|flag| flag.date == date && flag.username == username)
+        .ok_or_else(|| AppError::FlagNotFound(date.to_string(), username.to_string()))?;
+
+    store
+        .update_flag_status(date, username, status)
+        .await
+        .map_err(AppError::database(
+            "Couldn't update flagged result in database",
+        ))?;
+
+    let (_, warning) = finish_admin_change(
+        AuditAction::Moderate,
+        format!(
+            "{} {username}'s flagged result for {date}",
+            moderation_verb(status)
+        ),
+        "",
+        now,
+        schedule,
+        store,
     )
+    .await;
+
+    Ok((FlaggedResult { status, ..flag }, warning))
+}
+
+/// Reads the flag to moderate from the moderation form. Each button submits `date/username` under the name of the
+/// action it takes.
+fn parse_moderation_form(form: &FormData) -> AppResult<(String, String, ModerationStatus)> {
+    let (value, status) = match (form.get_field("approve"), form.get_field("reject")) {
+        (Some(value), _) => (value, ModerationStatus::Approved),
+        (None, Some(value)) => (value, ModerationStatus::Rejected),
+        (None, None) => {
+            return Err(AppError::BadRequest(String::from(
+                "Choose a result to approve or reject",
+            )))
+        }
+    };
+
+    let (date, username) = value
+        .split_once('/')
+        .ok_or_else(|| AppError::BadRequest(format!("Couldn't process flagged result {value}")))?;
+
+    Ok((date.to_string(), username.to_string(), status))
+}
+
+async fn handle_moderation<T>(req: &mut Request, ctx: &RouteContext<T>) -> Result<Response> {
+    match authorize_admin(req, ctx) {
+        Ok(()) => {}
+        Err(e @ AppError::Unauthorized) => {
+            return admin_login_page("/admin/moderation", Some(e.to_string()))
+        }
+        Err(e) => return error_page(&e),
+    }
+
+    let form = req.form_data().await?;
+
+    let store = match get_store(&ctx.env) {
+        Ok(store) => store,
+        Err(e) => return error_page(&e),
+    };
+    let moderated = async {
+        let (date, username, status) = parse_moderation_form(&form)?;
+        let schedule = get_season_schedule(&ctx.env)?;
+        moderate_result(&date, &username, status, Utc::now(), &schedule, &store).await
+    };
+
+    let (mut template, status) = match moderated.await {
+        Ok((flag, warning)) => (
+            ModerationTemplate {
+                message: Some(format!(
+                    "{} {}'s result for {}",
+                    moderation_verb(flag.status),
+                    flag.username,
+                    flag.date
+                )),
+                warning,
+                ..Default::default()
+            },
+            200,
+        ),
+        Err(e) if e.status() < 500 => (
+            ModerationTemplate {
+                error: Some(e.to_string()),
+                ..Default::default()
+            },
+            e.status(),
+        ),
+        Err(e) => return error_page(&e),
+    };
+
+    template.flags = match fetch_flagged_results(&store).await {
+        Ok(flags) => flags,
+        Err(e) => return error_page(&e),
+    };
+    let html = template.render().map_err(|e| e.to_string())?;
+
+    Ok(Response::from_html(html)?.with_status(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory_store::InMemoryStore;
+    use crate::models::FlagReason;
+    use pollster::block_on;
+
+    fn rebuilt_store() -> InMemoryStore {
+        let store = InMemoryStore::from_fixtures();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        block_on(rebuild_leaderboards(
+            &SeasonSchedule::default(),
+            today,
+            &store,
+        ))
+        .unwrap();
+        store
+    }
+
+    #[test]
+    fn test_rebuild_leaderboards_writes_every_leaderboard() {
+        let store = rebuilt_store();
+
+        for db_name in ["all_rust", "last_30_rust", "last_90_rust"] {
+            let leaderboard = block_on(store.fetch_leaderboard_from_db(db_name)).unwrap();
+            assert_eq!(leaderboard.len(), 4, "{db_name}");
+            assert_eq!(leaderboard[0].username, "carol", "{db_name}");
+        }
+
+        let history = block_on(store.fetch_rating_history("dave")).unwrap();
+        assert_eq!(history.len(), 5);
+    }
+
+    #[test]
+    fn test_rebuild_archives_completed_seasons() {
+        let store = InMemoryStore::from_fixtures();
+        let season = |id: &str, start_date: &str, end_date: &str| Season {
+            id: id.to_string(),
+            name: format!("Season {id}"),
+            start_date: start_date.to_string(),
+            end_date: end_date.to_string(),
+        };
+        let schedule = SeasonSchedule::Custom(vec![
+            season("early", "2026-10-05", "2026-10-08"),
+            season("late", "2026-10-09", "2026-10-20"),
+            season("next", "2026-11-01", "2026-11-30"),
+        ]);
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        block_on(rebuild_leaderboards(&schedule, today, &store)).unwrap();
+
+        let early = block_on(store.fetch_season_standings("early")).unwrap();
+        assert_eq!(early.len(), 4);
+        assert_eq!(early[0].rank, 1);
+        assert!(block_on(store.fetch_season_standings("late"))
+            .unwrap()
+            .is_empty());
+
+        let summaries = block_on(fetch_season_summaries(&schedule, today, &store)).unwrap();
+        let statuses: Vec<(&str, SeasonStatus, usize)> = summaries
+            .iter()
+            .map(|summary| {
+                (
+                    summary.season.id.as_str(),
+                    summary.status,
+                    summary.standings.len(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("next", SeasonStatus::Upcoming, 0),
+                ("late", SeasonStatus::Current, 3),
+                ("early", SeasonStatus::Completed, 3),
+            ]
+        );
+        assert_eq!(summaries[2].standings[0].username, early[0].username);
+
+        let late = block_on(fetch_season("late", &schedule, today, &store)).unwrap();
+        assert_eq!(late.standings.len(), 4);
+
+        let html = block_on(render_seasons(&schedule, today, &store)).unwrap();
+        assert!(html.contains("<a href=\"/seasons/early\">Season early</a>"));
+        assert!(html.contains("In progress"));
+
+        let html = block_on(render_season("early", &schedule, today, &store)).unwrap();
+        assert!(html.contains("Final standings"));
+
+        let missing = block_on(render_season("summer", &schedule, today, &store)).unwrap_err();
+        assert_eq!(missing.status(), 404);
+    }
+
+    #[test]
+    fn test_rebuild_leaves_out_flagged_results_until_approved() {
+        let store = InMemoryStore::from_fixtures();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let dave_num_played = |store: &InMemoryStore| {
+            block_on(store.fetch_leaderboard_from_db("all_rust"))
+                .unwrap()
+                .into_iter()
+                .find(|entry| entry.username == "dave")
+                .unwrap()
+                .num_played
+        };
+
+        block_on(rebuild_leaderboards(
+            &SeasonSchedule::default(),
+            today,
+            &store,
+        ))
+        .unwrap();
+        assert!(block_on(store.fetch_flagged_results()).unwrap().is_empty());
+        assert_eq!(dave_num_played(&store), 5);
+
+        let submission = Submission {
+            date: String::from("2026-10-13"),
+            username: String::from("dave"),
+            time: 4,
+        };
+        block_on(submit_results(vec![submission], today, &store)).unwrap();
+        block_on(rebuild_leaderboards(
+            &SeasonSchedule::default(),
+            today,
+            &store,
+        ))
+        .unwrap();
+
+        let flags = block_on(store.fetch_flagged_results()).unwrap();
+        assert_eq!(flags.len(), 1);
+        assert_eq!(flags[0].reason, FlagReason::ImpossibleTime);
+        assert_eq!(flags[0].status, ModerationStatus::Pending);
+        assert_eq!(dave_num_played(&store), 5);
+        assert!(block_on(store.fetch_puzzle_difficulty("2026-10-13"))
+            .unwrap()
+            .is_none());
+        assert!(block_on(store.fetch_result_scores("2026-10-13"))
+            .unwrap()
+            .is_empty());
+
+        let html = block_on(render_moderation(&store)).unwrap();
+        assert!(html.contains("name=\"approve\" value=\"2026-10-13/dave\""));
+
+        let (flag, warning) = block_on(moderate_result(
+            "2026-10-13",
+            "dave",
+            ModerationStatus::Approved,
+            Utc::now(),
+            &SeasonSchedule::default(),
+            &store,
+        ))
+        .unwrap();
+        assert_eq!(flag.status, ModerationStatus::Approved);
+        assert_eq!(warning, None);
+        assert_eq!(dave_num_played(&store), 6);
+        let audit_log = block_on(store.fetch_audit_log()).unwrap();
+        assert_eq!(audit_log.len(), 1);
+        assert_eq!(
+            audit_log[0].description,
+            "Approved dave's flagged result for 2026-10-13"
+        );
+        let missing = block_on(moderate_result(
+            "2026-10-13",
+            "alice",
+            ModerationStatus::Approved,
+            Utc::now(),
+            &SeasonSchedule::default(),
+            &store,
+        ))
+        .unwrap_err();
+        assert_eq!(missing.status(), 404);
+        assert_eq!(block_on(store.fetch_flagged_results()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_unapproved_flags_are_left_out_of_podium_and_h2h() {
+        let store = rebuilt_store();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let dave_vs_carol = |store: &InMemoryStore| {
+            let matrix = block_on(fetch_h2h_matrix(None, store)).unwrap();
+            let index = |username: &str| {
+                matrix
+                    .usernames
+                    .iter()
+                    .position(|name| name == username)
+                    .unwrap()
+            };
+            let stats = block_on(api_h2h(String::from("dave"), String::from("carol"), store))
+                .unwrap()
+                .stats
+                .unwrap();
+            assert_eq!(
+                matrix.records[index("dave")][index("carol")].total_matches,
+                stats.total_matches
+            );
+            stats.total_matches
+        };
+        let fastest_time = |store: &InMemoryStore| {
+            block_on(store.fetch_podium_data(&PodiumFilter::default())).unwrap()[0].time
+        };
+        let total_matches = dave_vs_carol(&store);
+
+        let submissions = vec![
+            Submission {
+                date: String::from("2026-10-13"),
+                username: String::from("dave"),
+                time: 4,
+            },
+            Submission {
+                date: String::from("2026-10-13"),
+                username: String::from("carol"),
+                time: 40,
+            },
+        ];
+        block_on(submit_results(submissions, today, &store)).unwrap();
+        block_on(rebuild_leaderboards(
+            &SeasonSchedule::default(),
+            today,
+            &store,
+        ))
+        .unwrap();
+        assert_eq!(block_on(store.fetch_flagged_results()).unwrap().len(), 1);
+        assert_eq!(dave_vs_carol(&store), total_matches);
+        assert_eq!(fastest_time(&store), 35);
+
+        block_on(moderate_result(
+            "2026-10-13",
+            "dave",
+            ModerationStatus::Approved,
+            Utc::now(),
+            &SeasonSchedule::default(),
+            &store,
+        ))
+        .unwrap();
+        assert_eq!(dave_vs_carol(&store), total_matches + 1);
+        assert_eq!(fastest_time(&store), 4);
+    }
+
+    #[test]
+    fn test_render_quarterly_seasons() {
+        let store = rebuilt_store();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+
+        let html = block_on(render_season(
+            "2026-q4",
+            &SeasonSchedule::Quarterly,
+            today,
+            &store,
+        ))
+        .unwrap();
+        assert!(html.contains("<h1>Q4 2026</h1>"));
+        assert!(html.contains("Standings so far"));
+    }
+
+    #[test]
+    fn test_render_index() {
+        let store = rebuilt_store();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+
+        let html = block_on(render_index(
+            LeaderboardWindow::All,
+            RatingSystemKind::TrueSkill,
+            today,
+            &store,
+        ))
+        .unwrap();
+        assert!(html.contains("<a href=\"/user/carol\">carol</a>"));
+
+        let html = block_on(render_index(
+            LeaderboardWindow::LastDays(30),
+            RatingSystemKind::Elo,
+            today,
+            &InMemoryStore::from_fixtures(),
+        ))
+        .unwrap();
+        assert!(html.contains("Last 30 Days, rated with Elo"));
+        assert!(html.contains("<a href=\"/user/dave\">dave</a>"));
+        assert!(html.contains("href=\"/index/last_30?system=glicko2\""));
+    }
+
+    #[test]
+    fn test_render_index_without_a_stored_leaderboard() {
+        let store = InMemoryStore::from_fixtures();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+
+        assert!(block_on(render_index(
+            LeaderboardWindow::All,
+            RatingSystemKind::TrueSkill,
+            today,
+            &store
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_favorites() {
+        let store = rebuilt_store();
+
+        let favorites = block_on(fetch_favorites(&store)).unwrap();
+        assert_eq!(favorites.date, "2026-10-12");
+        assert_eq!(favorites.predictions.len(), 4);
+        assert_eq!(favorites.predictions[0].username, "carol");
+        let total: f64 = favorites
+            .predictions
+            .iter()
+            .map(|prediction| prediction.win_probability)
+            .sum();
+        assert!((total - 1.0).abs() < 1e-6);
+        assert!(favorites.predictions[0].expected_rank < favorites.predictions[3].expected_rank);
+
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let html = block_on(render_index(
+            LeaderboardWindow::All,
+            RatingSystemKind::TrueSkill,
+            today,
+            &store,
+        ))
+        .unwrap();
+        assert!(html.contains("Tomorrow's Favorites"));
+
+        let html = block_on(render_index(
+            LeaderboardWindow::LastDays(30),
+            RatingSystemKind::Elo,
+            today,
+            &InMemoryStore::from_fixtures(),
+        ))
+        .unwrap();
+        assert!(!html.contains("Tomorrow's Favorites"));
+    }
+
+    #[test]
+    fn test_load_leaderboard_for_a_custom_window() {
+        let store = InMemoryStore::from_fixtures();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+
+        // alice and dave skipped 2026-10-11.
+        let window =
+            parse_leaderboard_window("all", Some("2026-10-10"), Some("2026-10-11")).unwrap();
+        let leaderboard = block_on(load_leaderboard(
+            window,
+            RatingSystemKind::TrueSkill,
+            today,
+            &store,
+        ))
+        .unwrap();
+        let mut played: Vec<(&str, i32)> = leaderboard
+            .iter()
+            .map(|entry| (entry.username.as_str(), entry.num_played))
+            .collect();
+        played.sort();
+        assert_eq!(
+            played,
+            vec![("alice", 1), ("bob", 2), ("carol", 2), ("dave", 1)]
+        );
+
+        let this_month = block_on(load_leaderboard(
+            LeaderboardWindow::ThisMonth,
+            RatingSystemKind::Elo,
+            today,
+            &store,
+        ))
+        .unwrap();
+        assert_eq!(this_month.len(), 4);
+
+        let html = block_on(render_index(
+            window,
+            RatingSystemKind::TrueSkill,
+            today,
+            &store,
+        ))
+        .unwrap();
+        assert!(html.contains("2026-10-10 to 2026-10-11"));
+        assert!(html.contains("value=\"2026-10-10\""));
+    }
+
+    #[test]
+    fn test_render_user() {
+        let store = rebuilt_store();
+
+        let html = block_on(render_user(String::from("alice"), &store)).unwrap();
+
+        assert!(html.contains("elo-plot"));
+        assert!(html.contains("weekday-plot"));
+        assert!(html.contains("prediction-plot"));
+        assert!(html.contains("2026-10-13 (next)"));
+        assert!(html.contains("00:35"));
+    }
+
+    #[test]
+    fn test_unknown_user_is_not_found() {
+        let store = rebuilt_store();
+
+        let error = block_on(render_user(String::from("alcie"), &store)).unwrap_err();
+        assert!(matches!(error, AppError::UserNotFound(ref username) if username == "alcie"));
+        assert_eq!(error.status(), 404);
+
+        let error =
+            block_on(api_h2h(String::from("alice"), String::from("erin"), &store)).unwrap_err();
+        assert_eq!(error.status(), 404);
+    }
+
+    #[test]
+    fn test_links_encode_usernames() {
+        let store = InMemoryStore::from_fixtures();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        block_on(submit_results(
+            vec![Submission {
+                date: String::from("2026-10-12"),
+                username: String::from("Zoë & 🦆"),
+                time: 90,
+            }],
+            today,
+            &store,
+        ))
+        .unwrap();
+
+        let html = block_on(render_history(String::from("2026-10-12"), &store)).unwrap();
+        assert!(html.contains("<a href=\"/user/Zo%C3%AB%20%26%20%F0%9F%A6%86\">Zoë &amp; 🦆</a>"));
+
+        let html = block_on(render_user(String::from("Zoë & 🦆"), &store)).unwrap();
+        assert!(html.contains("Statistics for Zoë &amp; 🦆"));
+    }
+
+    #[test]
+    fn test_invalid_history_date() {
+        let store = InMemoryStore::from_fixtures();
+
+        let error = block_on(render_history(String::from("2026-13-01"), &store)).unwrap_err();
+        assert!(matches!(error, AppError::InvalidDate(_)));
+        assert_eq!(error.status(), 400);
+    }
+
+    #[test]
+    fn test_render_history_and_podium() {
+        let store = rebuilt_store();
+
+        let html = block_on(render_history(String::from("2026-10-08"), &store)).unwrap();
+        assert!(html.contains("carol"));
+        assert!(html.contains("Difficulty"));
+        assert!(html.contains("<td>+1.41</td>"));
+
+        let podium = block_on(store.fetch_podium_data(&PodiumFilter::default())).unwrap();
+        assert_eq!(podium.len(), 10);
+        assert_eq!((podium[0].username.as_str(), podium[0].time), ("alice", 35));
+        assert!(
+            block_on(render_podium(PodiumFilter::default(), false, &store))
+                .unwrap()
+                .contains("alice")
+        );
+    }
+
+    #[test]
+    fn test_render_podium_with_filter() {
+        let store = rebuilt_store();
+        let filter = parse_podium_filter([("weekday", "sat"), ("limit", "3")]).unwrap();
+
+        let podium = block_on(store.fetch_podium_data(&filter)).unwrap();
+        let usernames: Vec<&str> = podium.iter().map(|entry| entry.username.as_str()).collect();
+        assert_eq!(usernames, vec!["carol", "alice", "bob"]);
+
+        let html = block_on(render_podium(filter.clone(), false, &store)).unwrap();
+        assert!(html.contains("Top 3 Saturday Times"));
+        assert!(html.contains("<option value=\"sat\" selected>"));
+
+        // alice's 35 is the fastest time ever, but carol's 39 on 2026-10-08 beat the field by more.
+        let relative =
+            block_on(fetch_relative_podium_data(&PodiumFilter::default(), &store)).unwrap();
+        assert_eq!(relative.len(), 10);
+        assert_eq!(
+            (
+                relative[0].result.username.as_str(),
+                relative[0].result.time
+            ),
+            ("carol", 39)
+        );
+        assert!((relative[0].field_score.unwrap() - 2.0_f64.sqrt()).abs() < 1e-9);
+        let relative = block_on(fetch_relative_podium_data(&filter, &store)).unwrap();
+        assert_eq!(relative.len(), 3);
+        assert!(relative
+            .iter()
+            .all(|scored| scored.result.date == "2026-10-10"));
+
+        let html = block_on(render_podium(filter, true, &store)).unwrap();
+        assert!(html.contains("Top 3 Saturday Times Relative to the Field"));
+        assert!(html.contains("action=\"/podium/relative\""));
+    }
+
+    #[test]
+    fn test_puzzle_difficulties() {
+        let store = rebuilt_store();
+
+        let difficulties = block_on(fetch_puzzle_difficulties(&store)).unwrap();
+        assert_eq!(difficulties.len(), 8);
+        assert_eq!(difficulties[0].date, "2026-10-10");
+        assert!(difficulties
+            .windows(2)
+            .all(|pair| pair[0].difficulty >= pair[1].difficulty));
+
+        let html = block_on(render_puzzles(&store)).unwrap();
+        assert!(html.contains("<a href=\"/history/2026-10-10\">2026-10-10</a>"));
+
+        let html = block_on(render_recent(&store)).unwrap();
+        assert!(html.contains(&difficulties[0].label));
+    }
+
+    #[test]
+    fn test_api_h2h() {
+        let store = rebuilt_store();
+
+        let response =
+            block_on(api_h2h(String::from("alice"), String::from("bob"), &store)).unwrap();
+
+        let stats = response.stats.unwrap();
+        assert_eq!((stats.wins_user1, stats.wins_user2, stats.ties), (5, 0, 1));
+        assert_eq!(stats.total_matches, 6);
+        assert!(stats.avg_time_difference < 0.0);
+        assert!(response.win_probability > 0.5);
+        assert_eq!(response.matches.len(), 6);
+        assert_eq!(response.matches.last().unwrap().win_difference, 5);
+
+        let html = block_on(render_h2h(
+            Some((String::from("alice"), String::from("bob"))),
+            &store,
+        ))
+        .unwrap();
+        assert!(html.contains("seconds faster than"));
+        assert!(html.contains("h2h-plot"));
+    }
+
+    #[test]
+    fn test_h2h_matrix_matches_pair_stats() {
+        let store = rebuilt_store();
+
+        let matrix = block_on(fetch_h2h_matrix(None, &store)).unwrap();
+        assert_eq!(matrix.usernames.len(), 4);
+        assert_eq!(matrix.usernames[0], "carol");
+
+        for (i, user1) in matrix.usernames.iter().enumerate() {
+            for (j, user2) in matrix.usernames.iter().enumerate().filter(|(j, _)| *j != i) {
+                let record = &matrix.records[i][j];
+                let stats = block_on(api_h2h(user1.clone(), user2.clone(), &store))
+                    .unwrap()
+                    .stats
+                    .unwrap();
+                assert_eq!(
+                    (record.wins, record.losses, record.ties),
+                    (stats.wins_user1, stats.wins_user2, stats.ties),
+                    "{user1} vs {user2}"
+                );
+                assert_eq!(record.total_matches, stats.total_matches);
+            }
+        }
+
+        let html = block_on(render_h2h_matrix(Some(2), &store)).unwrap();
+        assert!(html.contains("<a href=\"/h2h/carol/"));
+        assert!(html.contains("hsl("));
+        assert!(!html.contains("dave"));
+    }
+
+    #[test]
+    fn test_compare_players() {
+        let store = rebuilt_store();
+        let usernames = vec![
+            String::from("dave"),
+            String::from("carol"),
+            String::from("alice"),
+        ];
+
+        let comparison = block_on(api_compare(usernames.clone(), &store)).unwrap();
+        let compared: Vec<&str> = comparison
+            .players
+            .iter()
+            .map(|player| player.entry.username.as_str())
+            .collect();
+        assert_eq!(compared, vec!["dave", "carol", "alice"]);
+        assert_eq!(comparison.win_probabilities[0][0], None);
+        let (dave_beats_carol, carol_beats_dave) = (
+            comparison.win_probabilities[0][1].unwrap(),
+            comparison.win_probabilities[1][0].unwrap(),
+        );
+        assert!(carol_beats_dave > 0.5);
+        assert!((dave_beats_carol + carol_beats_dave - 1.0).abs() < 1e-9);
+
+        let html = block_on(render_compare(usernames, &store)).unwrap();
+        assert!(html.contains("value=\"carol\" checked"));
+        assert!(html.contains("box-plot"));
+        assert!(html.contains("<a href=\"/h2h/dave/carol\">"));
+
+        let html = block_on(render_compare(Vec::new(), &store)).unwrap();
+        assert!(!html.contains("Win Probabilities"));
+
+        let empty = block_on(api_compare(Vec::new(), &store)).unwrap_err();
+        assert_eq!(empty.status(), 400);
+        let unknown = block_on(api_compare(
+            vec![String::from("carol"), String::from("zoe")],
+            &store,
+        ))
+        .unwrap_err();
+        assert_eq!(unknown.status(), 404);
+    }
+
+    #[test]
+    fn test_api_recent_and_user() {
+        let store = rebuilt_store();
+
+        let recent = block_on(api_recent(&store)).unwrap();
+        assert_eq!(recent.len(), 10);
+        assert_eq!(recent[0].date, "2026-10-12");
+        assert_eq!(recent[0].results.len(), 4);
+        assert_eq!(recent[0].results[0].username, "alice");
+        assert_eq!(recent[3].date, "2026-10-09");
+        assert!(!recent[3]
+            .results
+            .iter()
+            .any(|entry| entry.username == "bob"));
+        assert!(recent[9].results.is_empty());
+
+        let user = block_on(api_user(String::from("bob"), &store)).unwrap();
+        assert_eq!(user.results.len(), 7);
+        assert_eq!(user.rating_history.len(), 7);
+        assert_eq!(user.predictions.len(), 7);
+        assert_eq!(user.predictions[0].date, "2026-10-13");
+        assert_eq!(user.prediction_history.len(), 2);
+        assert_eq!(
+            user.prediction_accuracy
+                .map(|accuracy| accuracy.num_predictions),
+            Some(2)
+        );
+        let mut expected: Vec<ScoredResult> =
+            score_results(&block_on(store.fetch_all_results()).unwrap())
+                .into_iter()
+                .filter(|scored| scored.result.username == "bob" && scored.field_score.is_some())
+                .collect();
+        expected.sort_by(|a, b| {
+            b.field_score
+                .unwrap_or_default()
+                .total_cmp(&a.field_score.unwrap_or_default())
+        });
+        let dates = |scored: &[ScoredResult]| -> Vec<String> {
+            scored
+                .iter()
+                .map(|scored| scored.result.date.clone())
+                .collect()
+        };
+        assert!(!user.best_relative_results.is_empty());
+        assert_eq!(
+            dates(&user.best_relative_results),
+            dates(&expected[..expected.len().min(BEST_RELATIVE_RESULTS_SHOWN)])
+        );
+
+        let stats = block_on(api_user(String::from("carol"), &store))
+            .unwrap()
+            .stats;
+        assert_eq!(
+            (stats.current_play_streak, stats.longest_play_streak),
+            (8, 8)
+        );
+        assert_eq!((stats.current_win_streak, stats.longest_win_streak), (0, 4));
+        assert_eq!(stats.participation_rate, 1.0);
+    }
+
+    #[test]
+    fn test_submit_results_reranks_existing_results() {
+        let store = InMemoryStore::from_fixtures();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        let submission = |username: &str, time: i32| Submission {
+            date: String::from("2026-10-11"),
+            username: username.to_string(),
+            time,
+        };
+
+        let inserted = block_on(submit_results(
+            vec![submission("alice", 30), submission("dave", 50)],
+            today,
+            &store,
+        ))
+        .unwrap();
+        assert_eq!(inserted.len(), 2);
+
+        let ranks: Vec<(String, i32)> = block_on(store.fetch_results("2026-10-11"))
+            .unwrap()
+            .into_iter()
+            .map(|entry| (entry.username, entry.rank))
+            .collect();
+        assert_eq!(
+            ranks,
+            vec![
+                (String::from("alice"), 1),
+                (String::from("carol"), 2),
+                (String::from("dave"), 3),
+                (String::from("bob"), 4),
+            ]
+        );
+
+        let duplicate =
+            block_on(submit_results(vec![submission("bob", 20)], today, &store)).unwrap_err();
+        assert_eq!(duplicate.status(), 409);
+
+        let future = block_on(submit_results(
+            vec![Submission {
+                date: String::from("2026-10-20"),
+                ..submission("erin", 20)
+            }],
+            today,
+            &store,
+        ))
+        .unwrap_err();
+        assert_eq!(future.status(), 400);
+    }
+
+    #[test]
+    fn test_submit_results_rejects_repeated_lines() {
+        let store = InMemoryStore::from_fixtures();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        let line = "I solved the 10/11/2026 New York Times Mini Crossword in 0:42!";
+        let submissions = parse_share_text(&format!("{line}\n{line}"), Some("erin"))
+            .unwrap()
+            .into_iter()
+            .map(|entry| Submission {
+                date: entry.date,
+                username: entry.username,
+                time: entry.time,
+            })
+            .collect();
+
+        let error = block_on(submit_results(submissions, today, &store)).unwrap_err();
+        assert_eq!(error.status(), 409);
+        assert!(block_on(store.fetch_results("2026-10-11"))
+            .unwrap()
+            .iter()
+            .all(|entry| entry.username != "erin"));
+    }
+
+    #[test]
+    fn test_submit_results_writes_nothing_if_any_date_conflicts() {
+        let store = InMemoryStore::from_fixtures();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        let text = "I solved the 10/09/2026 New York Times Mini Crossword in 0:42!\n\
+                    I solved the 10/12/2026 New York Times Mini Crossword in 0:30!";
+        let submissions = parse_share_text(text, Some("bob"))
+            .unwrap()
+            .into_iter()
+            .map(|entry| Submission {
+                date: entry.date,
+                username: entry.username,
+                time: entry.time,
+            })
+            .collect();
+
+        let error = block_on(submit_results(submissions, today, &store)).unwrap_err();
+        assert_eq!(error.status(), 409);
+        assert!(block_on(store.fetch_results("2026-10-09"))
+            .unwrap()
+            .iter()
+            .all(|entry| entry.username != "bob"));
+    }
+
+    #[test]
+    fn test_merge_keeps_moderation_decisions() {
+        let store = InMemoryStore::from_fixtures();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let now = Utc::now();
+        let schedule = SeasonSchedule::default();
+        let submissions = ["2026-10-13", "2026-10-14"]
+            .into_iter()
+            .map(|date| Submission {
+                date: date.to_string(),
+                username: String::from("dave"),
+                time: 4,
+            })
+            .collect();
+        block_on(submit_results(submissions, today, &store)).unwrap();
+        block_on(rebuild_leaderboards(&schedule, today, &store)).unwrap();
+        for (date, status) in [
+            ("2026-10-13", ModerationStatus::Approved),
+            ("2026-10-14", ModerationStatus::Rejected),
+        ] {
+            block_on(moderate_result(
+                date, "dave", status, now, &schedule, &store,
+            ))
+            .unwrap();
+        }
+
+        let merge = Correction::MergeUsers {
+            from: String::from("dave"),
+            to: String::from("david"),
+        };
+        block_on(apply_correction(merge, "", now, &schedule, &store)).unwrap();
+
+        let flags: Vec<(String, String, ModerationStatus)> =
+            block_on(store.fetch_flagged_results())
+                .unwrap()
+                .into_iter()
+                .map(|flag| (flag.date, flag.username, flag.status))
+                .collect();
+        assert_eq!(
+            flags,
+            vec![
+                (
+                    String::from("2026-10-14"),
+                    String::from("david"),
+                    ModerationStatus::Rejected
+                ),
+                (
+                    String::from("2026-10-13"),
+                    String::from("david"),
+                    ModerationStatus::Approved
+                ),
+            ]
+        );
+        let david = block_on(store.fetch_leaderboard_from_db("all_rust"))
+            .unwrap()
+            .into_iter()
+            .find(|entry| entry.username == "david")
+            .unwrap();
+        assert_eq!(david.num_played, 6);
+        assert_eq!(
+            block_on(store.fetch_rating_history("david")).unwrap().len(),
+            6
+        );
+        assert!(block_on(store.fetch_rating_history("dave"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_corrections_clear_flags() {
+        let store = InMemoryStore::from_fixtures();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let now = Utc::now();
+        let schedule = SeasonSchedule::default();
+        let flags = |store: &InMemoryStore| -> Vec<(String, ModerationStatus)> {
+            block_on(store.fetch_flagged_results())
+                .unwrap()
+                .into_iter()
+                .map(|flag| (flag.username, flag.status))
+                .collect()
+        };
+        let edit = |time: i32| Correction::EditTime {
+            date: String::from("2026-10-13"),
+            username: String::from("dave"),
+            time,
+        };
+        let submission = Submission {
+            date: String::from("2026-10-13"),
+            username: String::from("dave"),
+            time: 4,
+        };
+        block_on(submit_results(vec![submission], today, &store)).unwrap();
+        block_on(rebuild_leaderboards(&schedule, today, &store)).unwrap();
+        block_on(moderate_result(
+            "2026-10-13",
+            "dave",
+            ModerationStatus::Rejected,
+            now,
+            &schedule,
+            &store,
+        ))
+        .unwrap();
+
+        block_on(apply_correction(edit(60), "", now, &schedule, &store)).unwrap();
+        assert!(flags(&store).is_empty());
+
+        block_on(apply_correction(edit(3), "", now, &schedule, &store)).unwrap();
+        assert_eq!(
+            flags(&store),
+            vec![(String::from("dave"), ModerationStatus::Pending)]
+        );
+
+        let delete = Correction::Delete {
+            date: String::from("2026-10-13"),
+            username: String::from("dave"),
+        };
+        block_on(apply_correction(delete, "", now, &schedule, &store)).unwrap();
+        assert!(flags(&store).is_empty());
+    }
+
+    #[test]
+    fn test_admin_pages_require_admin_secret() {
+        let missing = check_admin_secret(None, "hunter2").unwrap_err();
+        assert_eq!(missing.status(), 401);
+        let wrong = check_admin_secret(Some("hunter3"), "hunter2").unwrap_err();
+        assert_eq!(wrong.status(), 401);
+        assert!(check_admin_secret(Some(" hunter2 "), "hunter2").is_ok());
+
+        let cookie = admin_cookie("hunter#2");
+        assert!(cookie.starts_with("admin_secret=hunter%232;"));
+        assert!(cookie.contains("HttpOnly"));
+        let header = "theme=dark; admin_secret=hunter%232";
+        assert_eq!(
+            cookie_value(header, ADMIN_COOKIE).as_deref(),
+            Some("hunter#2")
+        );
+        assert_eq!(cookie_value("theme=dark", ADMIN_COOKIE), None);
+
+        assert_eq!(
+            admin_redirect_path(Some(String::from("/admin/moderation"))),
+            "/admin/moderation"
+        );
+        assert_eq!(
+            admin_redirect_path(Some(String::from("https://example.com"))),
+            "/admin"
+        );
+        assert_eq!(admin_redirect_path(None), "/admin");
+
+        let html = AdminLoginTemplate {
+            next: String::from("/admin/moderation"),
+            error: None,
+        }
+        .render()
+        .unwrap();
+        assert!(html.contains("name=\"next\" value=\"/admin/moderation\""));
+    }
+
+    #[test]
+    fn test_apply_correction() {
+        let store = rebuilt_store();
+        let now = Utc::now();
+        let schedule = SeasonSchedule::default();
+        let ranks = |date: &str| -> Vec<(String, i32)> {
+            block_on(store.fetch_results(date))
+                .unwrap()
+                .into_iter()
+                .map(|entry| (entry.username, entry.rank))
+                .collect()
+        };
+        let edit = Correction::EditTime {
+            date: String::from("2026-10-12"),
+            username: String::from("bob"),
+            time: 30,
+        };
+
+        let (entry, warning) =
+            block_on(apply_correction(edit, " typo ", now, &schedule, &store)).unwrap();
+        assert_eq!(entry.action, AuditAction::EditTime);
+        assert_eq!(
+            entry.description,
+            "Changed bob's time for 2026-10-12 from 49 to 30 seconds"
+        );
+        assert_eq!(entry.note, "typo");
+        assert_eq!(warning, None);
+        assert_eq!(
+            ranks("2026-10-12"),
+            vec![
+                (String::from("bob"), 1),
+                (String::from("alice"), 2),
+                (String::from("carol"), 3),
+                (String::from("dave"), 4),
+            ]
+        );
+
+        let delete = Correction::Delete {
+            date: String::from("2026-10-05"),
+            username: String::from("alice"),
+        };
+        block_on(apply_correction(delete, "", now, &schedule, &store)).unwrap();
+        assert_eq!(
+            ranks("2026-10-05"),
+            vec![
+                (String::from("carol"), 1),
+                (String::from("bob"), 2),
+                (String::from("dave"), 3),
+            ]
+        );
+
+        let merge = Correction::MergeUsers {
+            from: String::from("dave"),
+            to: String::from("david"),
+        };
+        block_on(apply_correction(merge, "", now, &schedule, &store)).unwrap();
+        let leaderboard = block_on(store.fetch_leaderboard_from_db("all_rust")).unwrap();
+        let david = leaderboard
+            .iter()
+            .find(|entry| entry.username == "david")
+            .unwrap();
+        assert_eq!(david.num_played, 5);
+        assert!(leaderboard.iter().all(|entry| entry.username != "dave"));
+
+        let missing = Correction::Delete {
+            date: String::from("2026-10-11"),
+            username: String::from("alice"),
+        };
+        let error = block_on(apply_correction(missing, "", now, &schedule, &store)).unwrap_err();
+        assert_eq!(error.status(), 404);
+
+        let conflict = Correction::MergeUsers {
+            from: String::from("bob"),
+            to: String::from("carol"),
+        };
+        let error = block_on(apply_correction(conflict, "", now, &schedule, &store)).unwrap_err();
+        assert_eq!(error.status(), 409);
+
+        let audit_log = block_on(store.fetch_audit_log()).unwrap();
+        let actions: Vec<AuditAction> = audit_log.iter().map(|entry| entry.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                AuditAction::MergeUsers,
+                AuditAction::Delete,
+                AuditAction::EditTime
+            ]
+        );
+        let html = block_on(render_admin(&store)).unwrap();
+        assert!(html.contains("Merged dave into david, moving 5 results"));
+    }
 }
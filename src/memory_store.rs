@@ -0,0 +1,490 @@
+use chrono::NaiveDate;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::models::{
+    AuditEntry, FlaggedResult, LeaderboardEntry, ModerationStatus, PodiumFilter, PuzzleDifficulty,
+    RatingSnapshot, ResultEntry, ResultScore, SeasonStanding, UserData,
+};
+use crate::store::{ResultStore, StoreError};
+use crate::util::{
+    build_user_data, exclude_flagged_results, merge_new_results, podium_filter_matches,
+    rerank_results,
+};
+
+/// A `ResultStore` that keeps everything in memory, used to test handlers without a database.
+#[derive(Default)]
+pub struct InMemoryStore {
+    results: RefCell<Vec<ResultEntry>>,
+    leaderboards: RefCell<HashMap<String, Vec<LeaderboardEntry>>>,
+    rating_history: RefCell<Vec<RatingSnapshot>>,
+    season_standings: RefCell<Vec<SeasonStanding>>,
+    puzzle_difficulties: RefCell<Vec<PuzzleDifficulty>>,
+    result_scores: RefCell<Vec<ResultScore>>,
+    flagged_results: RefCell<Vec<FlaggedResult>>,
+    audit_log: RefCell<Vec<AuditEntry>>,
+}
+
+impl InMemoryStore {
+    /// Creates a store containing `results` and no leaderboards or rating history.
+    pub fn new(results: Vec<ResultEntry>) -> Self {
+        Self {
+            results: RefCell::new(results),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a store seeded with the results in `fixtures/results.json`.
+    pub fn from_fixtures() -> Self {
+        Self::new(serde_json::from_str(include_str!("../fixtures/results.json")).unwrap())
+    }
+
+    /// Re-ranks the stored results for a date.
+    fn rerank(&self, date: &str) {
+        let entries: Vec<ResultEntry> = self
+            .sorted_results()
+            .into_iter()
+            .filter(|entry| entry.date == date)
+            .collect();
+
+        let mut results = self.results.borrow_mut();
+        for reranked in rerank_results(entries) {
+            if let Some(entry) = results
+                .iter_mut()
+                .find(|entry| entry.date == date && entry.username == reranked.username)
+            {
+                entry.rank = reranked.rank;
+            }
+        }
+    }
+
+    /// Returns every stored result, sorted by date and then time.
+    fn sorted_results(&self) -> Vec<ResultEntry> {
+        let mut results = self.results.borrow().clone();
+        results.sort_by(|a, b| (&a.date, a.time, &a.username).cmp(&(&b.date, b.time, &b.username)));
+        results
+    }
+}
+
+impl ResultStore for InMemoryStore {
+    async fn fetch_results(&self, date: &str) -> Result<Vec<ResultEntry>, StoreError> {
+        Ok(self
+            .sorted_results()
+            .into_iter()
+            .filter(|entry| entry.date == date)
+            .collect())
+    }
+
+    async fn fetch_all_results(&self) -> Result<Vec<ResultEntry>, StoreError> {
+        Ok(self.sorted_results())
+    }
+
+    async fn fetch_results_since(&self, date: &str) -> Result<Vec<ResultEntry>, StoreError> {
+        Ok(self
+            .sorted_results()
+            .into_iter()
+            .filter(|entry| entry.date.as_str() >= date)
+            .collect())
+    }
+
+    async fn insert_results(
+        &self,
+        date: &str,
+        new_entries: Vec<ResultEntry>,
+    ) -> Result<Vec<ResultEntry>, StoreError> {
+        let existing_entries = self.fetch_results(date).await?;
+        let (inserted_entries, reranked_entries) =
+            merge_new_results(date, &existing_entries, new_entries)?;
+
+        let mut results = self.results.borrow_mut();
+        for reranked in &reranked_entries {
+            if let Some(entry) = results
+                .iter_mut()
+                .find(|entry| entry.date == date && entry.username == reranked.username)
+            {
+                entry.rank = reranked.rank;
+            }
+        }
+        results.extend(inserted_entries.iter().cloned());
+
+        Ok(inserted_entries)
+    }
+
+    async fn update_result_time(
+        &self,
+        date: &str,
+        username: &str,
+        time: i32,
+    ) -> Result<(), StoreError> {
+        for entry in self
+            .results
+            .borrow_mut()
+            .iter_mut()
+            .filter(|entry| entry.date == date && entry.username == username)
+        {
+            entry.time = time;
+        }
+        self.rerank(date);
+
+        Ok(())
+    }
+
+    async fn delete_result(&self, date: &str, username: &str) -> Result<(), StoreError> {
+        self.results
+            .borrow_mut()
+            .retain(|entry| entry.date != date || entry.username != username);
+        self.rerank(date);
+
+        Ok(())
+    }
+
+    async fn rename_user(&self, from: &str, to: &str) -> Result<(), StoreError> {
+        for entry in self
+            .results
+            .borrow_mut()
+            .iter_mut()
+            .filter(|entry| entry.username == from)
+        {
+            entry.username = to.to_string();
+        }
+        for flag in self
+            .flagged_results
+            .borrow_mut()
+            .iter_mut()
+            .filter(|flag| flag.username == from)
+        {
+            flag.username = to.to_string();
+        }
+        for snapshot in self
+            .rating_history
+            .borrow_mut()
+            .iter_mut()
+            .filter(|snapshot| snapshot.username == from)
+        {
+            snapshot.username = to.to_string();
+        }
+        for score in self
+            .result_scores
+            .borrow_mut()
+            .iter_mut()
+            .filter(|score| score.username == from)
+        {
+            score.username = to.to_string();
+        }
+
+        Ok(())
+    }
+
+    async fn fetch_audit_log(&self) -> Result<Vec<AuditEntry>, StoreError> {
+        Ok(self.audit_log.borrow().iter().rev().cloned().collect())
+    }
+
+    async fn insert_audit_entry(&self, entry: &AuditEntry) -> Result<(), StoreError> {
+        self.audit_log.borrow_mut().push(entry.clone());
+
+        Ok(())
+    }
+
+    async fn fetch_most_recent_crossword_date(&self) -> Result<NaiveDate, StoreError> {
+        let results = self.results.borrow();
+        let date = results
+            .iter()
+            .map(|entry| entry.date.as_str())
+            .max()
+            .ok_or(StoreError::NoResults)?;
+
+        Ok(NaiveDate::parse_from_str(date, "%Y-%m-%d")?)
+    }
+
+    async fn fetch_usernames_sorted_by_elo(&self) -> Result<Vec<String>, StoreError> {
+        Ok(self
+            .fetch_leaderboard_from_db("all_rust")
+            .await?
+            .into_iter()
+            .map(|entry| entry.username)
+            .collect())
+    }
+
+    async fn fetch_podium_data(
+        &self,
+        filter: &PodiumFilter,
+    ) -> Result<Vec<ResultEntry>, StoreError> {
+        let mut results: Vec<ResultEntry> =
+            exclude_flagged_results(self.sorted_results(), &self.flagged_results.borrow())
+                .into_iter()
+                .filter(|entry| podium_filter_matches(filter, &entry.date))
+                .collect();
+        results.sort_by_key(|entry| entry.time);
+        results.truncate(filter.limit);
+
+        Ok(results)
+    }
+
+    async fn fetch_user_data(&self, username: &str) -> Result<UserData, StoreError> {
+        let mut all_times: Vec<ResultEntry> = self
+            .sorted_results()
+            .into_iter()
+            .filter(|entry| entry.username == username)
+            .collect();
+        all_times.sort_by_key(|entry| entry.time);
+
+        Ok(build_user_data(all_times))
+    }
+
+    async fn fetch_leaderboard_from_db(
+        &self,
+        db_name: &str,
+    ) -> Result<Vec<LeaderboardEntry>, StoreError> {
+        let mut leaderboard = self
+            .leaderboards
+            .borrow()
+            .get(db_name)
+            .cloned()
+            .ok_or_else(|| StoreError::UnknownLeaderboard(db_name.to_string()))?;
+        leaderboard.sort_by(|a, b| b.elo.total_cmp(&a.elo));
+
+        Ok(leaderboard)
+    }
+
+    async fn replace_leaderboard_in_db(
+        &self,
+        db_name: &str,
+        entries: &[LeaderboardEntry],
+    ) -> Result<(), StoreError> {
+        self.leaderboards
+            .borrow_mut()
+            .insert(db_name.to_string(), entries.to_vec());
+
+        Ok(())
+    }
+
+    async fn fetch_rating_history(
+        &self,
+        username: &str,
+    ) -> Result<Vec<RatingSnapshot>, StoreError> {
+        let mut history: Vec<RatingSnapshot> = self
+            .rating_history
+            .borrow()
+            .iter()
+            .filter(|snapshot| snapshot.username == username)
+            .cloned()
+            .collect();
+        history.sort_by(|a, b| a.date.cmp(&b.date));
+
+        Ok(history)
+    }
+
+    async fn replace_rating_history_in_db(
+        &self,
+        history: &[RatingSnapshot],
+    ) -> Result<(), StoreError> {
+        *self.rating_history.borrow_mut() = history.to_vec();
+
+        Ok(())
+    }
+
+    async fn fetch_season_standings(
+        &self,
+        season_id: &str,
+    ) -> Result<Vec<SeasonStanding>, StoreError> {
+        let mut standings: Vec<SeasonStanding> = self
+            .season_standings
+            .borrow()
+            .iter()
+            .filter(|standing| standing.season_id == season_id)
+            .cloned()
+            .collect();
+        standings.sort_by_key(|standing| standing.rank);
+
+        Ok(standings)
+    }
+
+    async fn fetch_season_podiums(&self) -> Result<Vec<SeasonStanding>, StoreError> {
+        let mut podiums: Vec<SeasonStanding> = self
+            .season_standings
+            .borrow()
+            .iter()
+            .filter(|standing| standing.rank <= 3)
+            .cloned()
+            .collect();
+        podiums.sort_by(|a, b| (&a.season_id, a.rank).cmp(&(&b.season_id, b.rank)));
+
+        Ok(podiums)
+    }
+
+    async fn replace_season_standings_in_db(
+        &self,
+        standings: &[SeasonStanding],
+    ) -> Result<(), StoreError> {
+        *self.season_standings.borrow_mut() = standings.to_vec();
+
+        Ok(())
+    }
+
+    async fn fetch_puzzle_difficulties(&self) -> Result<Vec<PuzzleDifficulty>, StoreError> {
+        let mut difficulties = self.puzzle_difficulties.borrow().clone();
+        difficulties.sort_by(|a, b| {
+            b.difficulty
+                .total_cmp(&a.difficulty)
+                .then_with(|| a.date.cmp(&b.date))
+        });
+
+        Ok(difficulties)
+    }
+
+    async fn fetch_puzzle_difficulty(
+        &self,
+        date: &str,
+    ) -> Result<Option<PuzzleDifficulty>, StoreError> {
+        Ok(self
+            .puzzle_difficulties
+            .borrow()
+            .iter()
+            .find(|difficulty| difficulty.date == date)
+            .cloned())
+    }
+
+    async fn fetch_puzzle_difficulties_since(
+        &self,
+        date: &str,
+    ) -> Result<Vec<PuzzleDifficulty>, StoreError> {
+        let mut difficulties: Vec<PuzzleDifficulty> = self
+            .puzzle_difficulties
+            .borrow()
+            .iter()
+            .filter(|difficulty| difficulty.date.as_str() >= date)
+            .cloned()
+            .collect();
+        difficulties.sort_by(|a, b| a.date.cmp(&b.date));
+
+        Ok(difficulties)
+    }
+
+    async fn replace_puzzle_difficulties_in_db(
+        &self,
+        difficulties: &[PuzzleDifficulty],
+    ) -> Result<(), StoreError> {
+        *self.puzzle_difficulties.borrow_mut() = difficulties.to_vec();
+
+        Ok(())
+    }
+
+    async fn fetch_result_scores(&self, date: &str) -> Result<Vec<ResultScore>, StoreError> {
+        Ok(self
+            .result_scores
+            .borrow()
+            .iter()
+            .filter(|score| score.date == date)
+            .cloned()
+            .collect())
+    }
+
+    async fn fetch_best_result_scores(
+        &self,
+        username: &str,
+        limit: usize,
+    ) -> Result<Vec<ResultScore>, StoreError> {
+        let mut scores: Vec<ResultScore> = self
+            .result_scores
+            .borrow()
+            .iter()
+            .filter(|score| score.username == username && score.field_score.is_some())
+            .cloned()
+            .collect();
+        scores.sort_by(|a, b| {
+            b.field_score
+                .unwrap_or_default()
+                .total_cmp(&a.field_score.unwrap_or_default())
+        });
+        scores.truncate(limit);
+
+        Ok(scores)
+    }
+
+    async fn fetch_relative_podium_data(
+        &self,
+        filter: &PodiumFilter,
+    ) -> Result<Vec<ResultScore>, StoreError> {
+        let mut scores: Vec<ResultScore> = self
+            .result_scores
+            .borrow()
+            .iter()
+            .filter(|score| {
+                score.field_score.is_some() && podium_filter_matches(filter, &score.date)
+            })
+            .cloned()
+            .collect();
+        scores.sort_by(|a, b| {
+            b.field_score
+                .unwrap_or_default()
+                .total_cmp(&a.field_score.unwrap_or_default())
+                .then_with(|| a.date.cmp(&b.date))
+        });
+        scores.truncate(filter.limit);
+
+        Ok(scores)
+    }
+
+    async fn replace_result_scores_in_db(&self, scores: &[ResultScore]) -> Result<(), StoreError> {
+        *self.result_scores.borrow_mut() = scores.to_vec();
+
+        Ok(())
+    }
+
+    async fn fetch_flagged_results(&self) -> Result<Vec<FlaggedResult>, StoreError> {
+        let mut flags = self.flagged_results.borrow().clone();
+        flags.sort_by(|a, b| {
+            b.date
+                .cmp(&a.date)
+                .then_with(|| a.username.cmp(&b.username))
+        });
+
+        Ok(flags)
+    }
+
+    async fn insert_flagged_results(&self, flags: &[FlaggedResult]) -> Result<(), StoreError> {
+        self.flagged_results
+            .borrow_mut()
+            .extend(flags.iter().cloned());
+
+        Ok(())
+    }
+
+    async fn update_flag_status(
+        &self,
+        date: &str,
+        username: &str,
+        status: ModerationStatus,
+    ) -> Result<(), StoreError> {
+        for flag in self
+            .flagged_results
+            .borrow_mut()
+            .iter_mut()
+            .filter(|flag| flag.date == date && flag.username == username)
+        {
+            flag.status = status;
+        }
+
+        Ok(())
+    }
+
+    async fn delete_flagged_result(&self, date: &str, username: &str) -> Result<(), StoreError> {
+        self.flagged_results
+            .borrow_mut()
+            .retain(|flag| flag.date != date || flag.username != username);
+
+        Ok(())
+    }
+
+    async fn fetch_user_trueskill_from_db(
+        &self,
+        username: &str,
+    ) -> Result<Option<(f64, f64)>, StoreError> {
+        Ok(self
+            .fetch_leaderboard_from_db("all_rust")
+            .await?
+            .into_iter()
+            .find(|entry| entry.username == username)
+            .map(|entry| (entry.mu, entry.sigma)))
+    }
+}
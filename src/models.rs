@@ -1,6 +1,7 @@
-use serde::Deserialize;
+use chrono::{NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct ResultEntry {
     pub date: String,
     pub time: i32,
@@ -8,7 +9,7 @@ pub struct ResultEntry {
     pub rank: i32,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct LeaderboardEntry {
     pub username: String,
     pub mu: f64,
@@ -19,12 +20,188 @@ pub struct LeaderboardEntry {
     pub elo: f64,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct Submission {
+    pub date: String,
+    pub username: String,
+    pub time: i32,
+}
+
+/// A change an admin wants to make to the stored results.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Correction {
+    /// Changes a user's time for a date.
+    EditTime {
+        date: String,
+        username: String,
+        time: i32,
+    },
+    /// Deletes a user's result for a date.
+    Delete { date: String, username: String },
+    /// Moves every result from one username to another, e.g. after a player renames themselves.
+    MergeUsers { from: String, to: String },
+}
+
+/// The kind of change an audit log entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    EditTime,
+    Delete,
+    MergeUsers,
+    Moderate,
+}
+
+/// A change an admin made to the stored results.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AuditEntry {
+    /// When the change was made, as an RFC 3339 timestamp.
+    pub timestamp: String,
+    pub action: AuditAction,
+    /// What changed, in words.
+    pub description: String,
+    /// Why the admin made the change. Empty if they didn't say.
+    pub note: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RatingSnapshot {
+    pub date: String,
+    pub username: String,
+    pub mu: f64,
+    pub sigma: f64,
+    pub elo: f64,
+}
+
+/// Narrows the podium down to the fastest `limit` results matching every filter that is set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PodiumFilter {
+    pub limit: usize,
+    pub weekday: Option<Weekday>,
+    /// The month of the year, from 1 to 12.
+    pub month: Option<u32>,
+    pub year: Option<i32>,
+}
+
+impl Default for PodiumFilter {
+    fn default() -> Self {
+        Self {
+            limit: 10,
+            weekday: None,
+            month: None,
+            year: None,
+        }
+    }
+}
+
+/// The results a leaderboard is computed from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LeaderboardWindow {
+    All,
+    /// The given number of days, ending at the most recent crossword date.
+    LastDays(i64),
+    /// The current calendar month.
+    ThisMonth,
+    /// The current calendar year.
+    ThisYear,
+    /// An inclusive date range, either end of which may be open.
+    Range(Option<NaiveDate>, Option<NaiveDate>),
+}
+
+/// A period whose results are ranked on their own, separately from the all-time leaderboard.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Season {
+    pub id: String,
+    pub name: String,
+    /// The first date of the season, in YYYY-MM-DD format.
+    pub start_date: String,
+    /// The last date of the season, in YYYY-MM-DD format.
+    pub end_date: String,
+}
+
+/// How the calendar is divided into seasons.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SeasonSchedule {
+    /// A season for every calendar month.
+    Monthly,
+    /// A season for every calendar quarter.
+    #[default]
+    Quarterly,
+    /// Only the listed seasons, which may have any length and may overlap.
+    Custom(Vec<Season>),
+}
+
+/// Where a season is relative to today.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SeasonStatus {
+    Upcoming,
+    Current,
+    Completed,
+}
+
+/// A player's final placing in a season, as written by the rebuild.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SeasonStanding {
+    pub season_id: String,
+    pub rank: i32,
+    pub username: String,
+    pub mu: f64,
+    pub sigma: f64,
+    pub average_time: f64,
+    pub num_wins: i32,
+    pub num_played: i32,
+    pub elo: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SeasonResults {
+    #[serde(flatten)]
+    pub season: Season,
+    pub status: SeasonStatus,
+    pub standings: Vec<SeasonStanding>,
+}
+
+/// Why the anomaly detector flagged a result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagReason {
+    /// Faster than anyone could plausibly solve a puzzle.
+    ImpossibleTime,
+    /// Far faster than the user's usual time.
+    PersonalOutlier,
+    /// Far faster than everyone else who solved the same puzzle.
+    FieldOutlier,
+}
+
+/// Where a flagged result is in the moderation queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationStatus {
+    #[default]
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// A result the anomaly detector flagged for review. Until it's approved, it doesn't count towards ratings.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FlaggedResult {
+    pub date: String,
+    pub username: String,
+    pub time: i32,
+    pub reason: FlagReason,
+    /// Why the result was flagged, in words.
+    pub detail: String,
+    pub status: ModerationStatus,
+}
+
 #[derive(Deserialize)]
 pub struct UsernameData {
     pub username: String,
 }
 
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct HeadToHeadData {
     #[serde(skip_deserializing)]
     pub user1: String,
@@ -35,17 +212,210 @@ pub struct HeadToHeadData {
     pub ties: i32,
     pub total_matches: i32,
     pub avg_time_difference: f64,
-    #[serde(skip_deserializing)]
+    #[serde(skip)]
     pub time_diff_description: String,
 }
 
+/// A single crossword two players both solved.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeadToHeadMatch {
+    pub date: String,
+    pub user1_time: i32,
+    pub user2_time: i32,
+    /// `user1_time - user2_time`, so negative when user1 was faster.
+    pub margin: i32,
+    /// The faster player, or `None` for a tie.
+    pub winner: Option<String>,
+    /// user1's wins minus user2's wins over every match up to and including this one.
+    pub win_difference: i32,
+}
+
+/// One player's record against another over every crossword they both played.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct HeadToHeadRecord {
+    pub wins: i32,
+    pub losses: i32,
+    pub ties: i32,
+    pub total_matches: i32,
+    /// The fraction of matches won, counting ties as half a win, or `None` if the players have never met.
+    pub win_rate: Option<f64>,
+}
+
+/// The head-to-head records of every pair in a group of players.
+#[derive(Debug, Clone, Serialize)]
+pub struct HeadToHeadMatrix {
+    pub usernames: Vec<String>,
+    /// `records[i][j]` is the record of `usernames[i]` against `usernames[j]`.
+    pub records: Vec<Vec<HeadToHeadRecord>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HeadToHeadResponse {
+    pub user1: String,
+    pub user2: String,
+    pub win_probability: f64,
+    pub stats: Option<HeadToHeadData>,
+    /// Every crossword both users played, oldest first.
+    pub matches: Vec<HeadToHeadMatch>,
+}
+
+/// A player's all-time stats, as shown on the compare page.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparedPlayer {
+    #[serde(flatten)]
+    pub entry: LeaderboardEntry,
+    pub median_time: f64,
+    pub best_time: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComparisonResponse {
+    pub players: Vec<ComparedPlayer>,
+    /// `win_probabilities[i][j]` is the probability that player i beats player j, or `None` when `i == j`.
+    pub win_probabilities: Vec<Vec<Option<f64>>>,
+}
+
+/// How a player is expected to finish in the next crossword.
+#[derive(Debug, Clone, Serialize)]
+pub struct FinishPrediction {
+    pub username: String,
+    pub elo: f64,
+    pub win_probability: f64,
+    /// The average finishing position, where 1.0 is first.
+    pub expected_rank: f64,
+    /// `rank_probabilities[k]` is the probability of finishing in position k + 1.
+    pub rank_probabilities: Vec<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FavoritesResponse {
+    /// The crossword whose solvers the predictions are for.
+    pub date: String,
+    /// The predictions for every solver, most likely winner first.
+    pub predictions: Vec<FinishPrediction>,
+}
+
+/// A solver's predicted time for a crossword, with an 80% prediction interval.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimePrediction {
+    pub date: String,
+    pub weekday: &'static str,
+    /// The median predicted time, in seconds.
+    pub predicted_time: f64,
+    pub low_time: f64,
+    pub high_time: f64,
+}
+
+/// A prediction made from a solver's earlier results, alongside the time they actually posted.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PredictionOutcome {
+    #[serde(flatten)]
+    pub prediction: TimePrediction,
+    pub actual_time: i32,
+}
+
+/// How close a solver's predicted times have come to their actual times.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PredictionAccuracy {
+    pub num_predictions: usize,
+    /// The median of `|actual / predicted - 1|`, so 0.2 means predictions are typically 20% off.
+    pub median_error: f64,
+    /// The fraction of actual times that fell within the prediction interval.
+    pub interval_coverage: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserResponse {
+    pub username: String,
+    pub stats: UserStats,
+    pub weekday_stats: Vec<WeekdayStats>,
+    pub best_relative_results: Vec<ScoredResult>,
+    pub results: Vec<ResultEntry>,
+    pub rating_history: Vec<RatingSnapshot>,
+    /// The user's predicted time for each of the next seven days, starting the day after the most recent crossword.
+    pub predictions: Vec<TimePrediction>,
+    /// How each of the user's results compared to the time predicted from their earlier results, oldest first.
+    pub prediction_history: Vec<PredictionOutcome>,
+    pub prediction_accuracy: Option<PredictionAccuracy>,
+    /// The accuracy of only the most recent predictions, to show whether the user is getting more predictable.
+    pub recent_prediction_accuracy: Option<PredictionAccuracy>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DailyResults {
+    pub date: String,
+    pub results: Vec<ResultEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
 #[derive(Debug)]
 pub struct UserData {
     pub all_times: Vec<ResultEntry>,
     pub times_excluding_saturday: Vec<ResultEntry>,
+    /// The results for each day of the week, indexed by days from Monday.
+    pub times_by_weekday: [Vec<ResultEntry>; 7],
 }
 
-#[derive(Debug, Deserialize)]
-pub struct Wrapper<T> {
-    pub inner: T,
+/// How a user does on a single day of the week.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeekdayStats {
+    pub weekday: &'static str,
+    pub count: usize,
+    pub average_time: f64,
+    pub median_time: f64,
+    pub best_time: i32,
+    pub win_rate: f64,
+}
+
+/// Streak and participation statistics for a single user. Streaks count consecutive calendar days.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UserStats {
+    pub days_played: usize,
+    pub current_play_streak: usize,
+    pub longest_play_streak: usize,
+    pub current_win_streak: usize,
+    pub longest_win_streak: usize,
+    /// The fraction of days since the user's first solve on which they played.
+    pub participation_rate: f64,
+    /// The most consecutive days the user has missed between two solves.
+    pub longest_gap: i64,
+}
+
+/// How hard a single puzzle was compared to how its solvers usually do.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PuzzleDifficulty {
+    pub date: String,
+    /// The median of each solver's time divided by their usual time. 1.0 is a typical puzzle, 2.0 took people
+    /// twice as long as usual.
+    pub difficulty: f64,
+    pub label: String,
+    pub median_time: f64,
+    pub num_solvers: usize,
+}
+
+/// A result alongside how it compares to the rest of that day's field and to the solver's own history.
+///
+/// Scores are z-scores of the log of the solve time, with the sign flipped so that positive means faster than
+/// usual. They're `None` when there isn't enough data to compare against.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoredResult {
+    #[serde(flatten)]
+    pub result: ResultEntry,
+    pub field_score: Option<f64>,
+    pub personal_score: Option<f64>,
+}
+
+/// The scores of a single result, as stored by the rebuild.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ResultScore {
+    pub date: String,
+    pub username: String,
+    pub time: i32,
+    pub rank: i32,
+    pub field_score: Option<f64>,
+    pub personal_score: Option<f64>,
 }
@@ -0,0 +1,247 @@
+use chrono::NaiveDate;
+use thiserror::Error;
+
+use crate::models::ResultEntry;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ParseError {
+    #[error("Parse error: Couldn't find any NYT Mini results in the pasted text")]
+    NoResults,
+    #[error("Parse error: Line {0} doesn't say who solved the puzzle")]
+    MissingUsername(usize),
+    #[error("Parse error: Line {0} has an invalid date")]
+    InvalidDate(usize),
+    #[error("Parse error: Line {0} has an invalid time")]
+    InvalidTime(usize),
+}
+
+/// Marker that starts the text NYT generates when sharing a solve, e.g.
+/// "I solved the 10/16/2026 New York Times Mini Crossword in 0:42!".
+const SOLVED_MARKER: &str = "i solved the ";
+
+/// Marker that starts a shared badge link, e.g.
+/// "https://www.nytimes.com/badges/games/mini.html?d=2026-10-16&t=42&c=...".
+const BADGE_MARKER: &str = "https://www.nytimes.com/badges/games/mini.html?";
+
+/// Parses pasted NYT Mini share text into unranked `ResultEntry` values.
+///
+/// Every non-empty line containing a shared result produces one entry. A line may be prefixed with the
+/// solver's name (e.g. "alice: I solved the ..."), optionally preceded by a bracketed chat timestamp;
+/// lines without a name are attributed to `default_username`. Lines that don't contain a result are ignored.
+///
+/// # Arguments
+///
+/// * `text` - The pasted text, containing one or more shared results.
+/// * `default_username` - The username to use for lines that don't name a solver.
+///
+/// # Returns
+///
+/// A `Result` containing the parsed entries with a rank of 0, or a `ParseError` for the first malformed line.
+pub fn parse_share_text(
+    text: &str,
+    default_username: Option<&str>,
+) -> Result<Vec<ResultEntry>, ParseError> {
+    let mut entries = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+
+        let (prefix, date, time) = if let Some(start) = find_ignore_ascii_case(line, SOLVED_MARKER)
+        {
+            let (date, time) =
+                parse_solved_text(&line[start + SOLVED_MARKER.len()..], line_number)?;
+            (&line[..start], date, time)
+        } else if let Some(start) = find_ignore_ascii_case(line, BADGE_MARKER) {
+            let (date, time) = parse_badge_query(&line[start + BADGE_MARKER.len()..], line_number)?;
+            (&line[..start], date, time)
+        } else {
+            continue;
+        };
+
+        let username = match parse_username_prefix(prefix) {
+            Some(username) => username.to_string(),
+            None => default_username
+                .map(str::trim)
+                .filter(|username| !username.is_empty())
+                .ok_or(ParseError::MissingUsername(line_number))?
+                .to_string(),
+        };
+
+        entries.push(ResultEntry {
+            date: date.format("%Y-%m-%d").to_string(),
+            time,
+            username,
+            rank: 0,
+        });
+    }
+
+    if entries.is_empty() {
+        return Err(ParseError::NoResults);
+    }
+
+    Ok(entries)
+}
+
+/// Finds the byte offset of `needle` in `haystack`, ignoring ASCII case.
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack.char_indices().map(|(i, _)| i).find(|&i| {
+        haystack.as_bytes()[i..]
+            .get(..needle.len())
+            .is_some_and(|candidate| candidate.eq_ignore_ascii_case(needle.as_bytes()))
+    })
+}
+
+/// Parses the remainder of a share message after "I solved the", which looks like
+/// "[Weekday] M/D/YYYY New York Times Mini Crossword in M:SS!".
+fn parse_solved_text(text: &str, line_number: usize) -> Result<(NaiveDate, i32), ParseError> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+
+    let date = words
+        .iter()
+        .find(|word| word.contains('/'))
+        .and_then(|word| NaiveDate::parse_from_str(word, "%m/%d/%Y").ok())
+        .ok_or(ParseError::InvalidDate(line_number))?;
+
+    let time = words
+        .iter()
+        .rposition(|word| word.eq_ignore_ascii_case("in"))
+        .and_then(|i| words.get(i + 1))
+        .and_then(|word| parse_clock_time(word.trim_end_matches(['!', '.'])))
+        .ok_or(ParseError::InvalidTime(line_number))?;
+
+    Ok((date, time))
+}
+
+/// Parses the query string of a shared badge link, where `d` is the date and `t` the time in seconds.
+fn parse_badge_query(query: &str, line_number: usize) -> Result<(NaiveDate, i32), ParseError> {
+    let query = query.split_whitespace().next().unwrap_or_default();
+    let param = |name: &str| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    };
+
+    let date = param("d")
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .ok_or(ParseError::InvalidDate(line_number))?;
+
+    let time = param("t")
+        .and_then(|time| time.parse::<i32>().ok())
+        .filter(|time| *time > 0)
+        .ok_or(ParseError::InvalidTime(line_number))?;
+
+    Ok((date, time))
+}
+
+/// Parses a clock time such as "0:42" or "1:02:03" into seconds.
+pub fn parse_clock_time(time: &str) -> Option<i32> {
+    let parts = time
+        .split(':')
+        .map(|part| part.parse::<i32>().ok().filter(|value| *value >= 0))
+        .collect::<Option<Vec<i32>>>()?;
+
+    match parts[..] {
+        [minutes, seconds] if seconds < 60 => Some(minutes * 60 + seconds),
+        [hours, minutes, seconds] if minutes < 60 && seconds < 60 => {
+            Some(hours * 3600 + minutes * 60 + seconds)
+        }
+        _ => None,
+    }
+}
+
+/// Extracts the solver's name from the text preceding a shared result, e.g. "alice:" or
+/// "[10/16/26, 8:03 AM] alice:".
+fn parse_username_prefix(prefix: &str) -> Option<&str> {
+    let prefix = prefix.trim();
+    let prefix = match prefix.strip_prefix('[') {
+        Some(rest) => rest.split_once(']').map_or(prefix, |(_, name)| name),
+        None => prefix,
+    };
+
+    Some(prefix.trim().trim_end_matches([':', '-']).trim()).filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_share_text_with_default_username() {
+        let entries = parse_share_text(
+            "I solved the 10/16/2026 New York Times Mini Crossword in 0:42!",
+            Some("alice"),
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].date, "2026-10-16");
+        assert_eq!(entries[0].time, 42);
+        assert_eq!(entries[0].username, "alice");
+    }
+
+    #[test]
+    fn test_parse_share_text_with_weekday_and_long_time() {
+        let entries = parse_share_text(
+            "I solved the Saturday 10/17/2026 New York Times Mini Crossword in 1:02:03!",
+            Some("alice"),
+        )
+        .unwrap();
+
+        assert_eq!(entries[0].date, "2026-10-17");
+        assert_eq!(entries[0].time, 3723);
+    }
+
+    #[test]
+    fn test_parse_share_text_with_multiple_named_lines() {
+        let text = "alice: I solved the 10/16/2026 New York Times Mini Crossword in 0:42!\n\
+                    \n\
+                    [10/16/26, 8:03 AM] Bob Smith: i solved the 10/16/2026 new york times mini crossword in 1:05!\n\
+                    good morning everyone\n\
+                    carol - https://www.nytimes.com/badges/games/mini.html?d=2026-10-16&t=37&c=abc&smid=url-share";
+
+        let entries = parse_share_text(text, None).unwrap();
+
+        let parsed: Vec<(&str, &str, i32)> = entries
+            .iter()
+            .map(|entry| (entry.username.as_str(), entry.date.as_str(), entry.time))
+            .collect();
+        assert_eq!(
+            parsed,
+            vec![
+                ("alice", "2026-10-16", 42),
+                ("Bob Smith", "2026-10-16", 65),
+                ("carol", "2026-10-16", 37),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_share_text_errors() {
+        let solved = |date: &str, time: &str| {
+            format!("I solved the {date} New York Times Mini Crossword in {time}!")
+        };
+
+        assert_eq!(
+            parse_share_text("good morning", Some("alice")).unwrap_err(),
+            ParseError::NoResults
+        );
+        assert_eq!(
+            parse_share_text(&solved("10/16/2026", "0:42"), None).unwrap_err(),
+            ParseError::MissingUsername(1)
+        );
+        assert_eq!(
+            parse_share_text(
+                &format!("\n{}", solved("13/45/2026", "0:42")),
+                Some("alice")
+            )
+            .unwrap_err(),
+            ParseError::InvalidDate(2)
+        );
+        assert_eq!(
+            parse_share_text(&solved("10/16/2026", "0:75"), Some("alice")).unwrap_err(),
+            ParseError::InvalidTime(1)
+        );
+    }
+}
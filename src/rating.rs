@@ -0,0 +1,1118 @@
+use skillratings::elo::{self, EloConfig, EloRating};
+use skillratings::glicko2::{self, Glicko2Config, Glicko2Rating};
+use skillratings::trueskill::{self, TrueSkillConfig, TrueSkillRating};
+use skillratings::weng_lin::{self, WengLinConfig, WengLinRating};
+use skillratings::{MultiTeamOutcome, Outcomes};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_2_SQRT_PI, PI, SQRT_2};
+use std::ops::{Div, Mul};
+
+use crate::models::{LeaderboardEntry, RatingSnapshot, ResultEntry};
+
+/// Initial mean skill of a new player.
+const MU: f64 = 25.0;
+/// Initial skill uncertainty of a new player.
+const SIGMA: f64 = MU / 3.0;
+/// Distance in skill that guarantees roughly a 76% chance of winning.
+const BETA: f64 = SIGMA / 2.0;
+/// Dynamic factor added to every player's uncertainty before each crossword.
+const TAU: f64 = SIGMA / 100.0;
+/// Probability that two players of equal skill tie.
+const DRAW_PROBABILITY: f64 = 0.10;
+/// Message-passing stops once no message changes by more than this.
+const MIN_DELTA: f64 = 0.0001;
+/// Upper bound on message-passing iterations per crossword.
+const MAX_ITERATIONS: usize = 10;
+/// How many slices `predict_finishing_order` splits the range of possible performances into.
+const PREDICTION_STEPS: usize = 400;
+
+/// A rating system that can replay crosswords to rank players.
+pub trait RatingSystem {
+    /// A single player's rating.
+    type Rating: Copy;
+
+    /// The rating of a player who hasn't played any crosswords yet.
+    fn initial_rating(&self) -> Self::Rating;
+
+    /// Updates the ratings of every player in a crossword.
+    ///
+    /// # Arguments
+    ///
+    /// * `ratings` - The players' ratings before the crossword, sorted by rank.
+    /// * `ranks` - The zero-based rank of each player, in ascending order. Equal ranks are ties.
+    ///
+    /// # Returns
+    ///
+    /// A vector containing the updated rating of each player, in the same order as `ratings`.
+    fn rate(&self, ratings: &[Self::Rating], ranks: &[usize]) -> Vec<Self::Rating>;
+
+    /// Converts a rating into the `(mu, sigma)` pair stored in the database.
+    fn mu_sigma(&self, rating: &Self::Rating) -> (f64, f64);
+
+    /// The score players are ranked by on the leaderboard.
+    fn elo(&self, rating: &Self::Rating) -> f64;
+
+    /// The probability that `player` beats `opponent`.
+    fn win_probability(&self, player: &Self::Rating, opponent: &Self::Rating) -> f64;
+}
+
+/// The rating systems the leaderboard can be computed with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RatingSystemKind {
+    #[default]
+    TrueSkill,
+    Glicko2,
+    WengLin,
+    Elo,
+}
+
+impl RatingSystemKind {
+    pub const ALL: [RatingSystemKind; 4] = [
+        RatingSystemKind::TrueSkill,
+        RatingSystemKind::Glicko2,
+        RatingSystemKind::WengLin,
+        RatingSystemKind::Elo,
+    ];
+
+    /// The identifier used for this rating system in URLs.
+    pub fn id(&self) -> &'static str {
+        match self {
+            RatingSystemKind::TrueSkill => "trueskill",
+            RatingSystemKind::Glicko2 => "glicko2",
+            RatingSystemKind::WengLin => "weng_lin",
+            RatingSystemKind::Elo => "elo",
+        }
+    }
+
+    /// The human-readable name of this rating system.
+    pub fn label(&self) -> &'static str {
+        match self {
+            RatingSystemKind::TrueSkill => "TrueSkill",
+            RatingSystemKind::Glicko2 => "Glicko-2",
+            RatingSystemKind::WengLin => "Weng-Lin",
+            RatingSystemKind::Elo => "Elo",
+        }
+    }
+
+    /// Parses a rating system from its URL identifier.
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.id() == id)
+    }
+
+    /// Replays every crossword in `results` with this rating system and builds the leaderboard.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - A slice of `ResultEntry` values covering every date that should contribute to the leaderboard.
+    ///
+    /// # Returns
+    ///
+    /// A vector of `LeaderboardEntry` structs, sorted by ELO in descending order.
+    pub fn compute_leaderboard(&self, results: &[ResultEntry]) -> Vec<LeaderboardEntry> {
+        match self {
+            RatingSystemKind::TrueSkill => compute_leaderboard(results, &TrueSkill),
+            RatingSystemKind::Glicko2 => compute_leaderboard(results, &Glicko2),
+            RatingSystemKind::WengLin => compute_leaderboard(results, &WengLin),
+            RatingSystemKind::Elo => compute_leaderboard(results, &Elo),
+        }
+    }
+}
+
+/// Replays every crossword in `results` and builds the leaderboard.
+///
+/// # Arguments
+///
+/// * `results` - A slice of `ResultEntry` values covering every date that should contribute to the leaderboard.
+/// * `system` - The rating system used to rate players.
+///
+/// # Returns
+///
+/// A vector of `LeaderboardEntry` structs, sorted by ELO in descending order.
+pub fn compute_leaderboard<S: RatingSystem>(
+    results: &[ResultEntry],
+    system: &S,
+) -> Vec<LeaderboardEntry> {
+    compute_leaderboard_with_history(results, system).0
+}
+
+/// Replays every crossword in `results`, building the leaderboard and a rating snapshot for every player on
+/// every date they played.
+///
+/// Crosswords are processed in date order. Each player's rank on a date is one more than the number of players
+/// with a strictly faster time, so tied times are treated as draws. Dates with a single player count towards
+/// the played/won/average-time statistics but don't move any ratings.
+///
+/// # Arguments
+///
+/// * `results` - A slice of `ResultEntry` values covering every date that should contribute to the leaderboard.
+/// * `system` - The rating system used to rate players.
+///
+/// # Returns
+///
+/// A tuple containing the `LeaderboardEntry` structs, sorted by ELO in descending order, and the
+/// `RatingSnapshot` structs, sorted by date.
+pub fn compute_leaderboard_with_history<S: RatingSystem>(
+    results: &[ResultEntry],
+    system: &S,
+) -> (Vec<LeaderboardEntry>, Vec<RatingSnapshot>) {
+    let mut results_by_date: BTreeMap<&str, Vec<&ResultEntry>> = BTreeMap::new();
+    for entry in results {
+        results_by_date
+            .entry(entry.date.as_str())
+            .or_default()
+            .push(entry);
+    }
+
+    let mut ratings: HashMap<&str, S::Rating> = HashMap::new();
+    let mut stats: HashMap<&str, (i32, i32, i64)> = HashMap::new();
+    let mut history = Vec::new();
+
+    for (date, entries) in results_by_date.iter_mut() {
+        entries.sort_by_key(|entry| entry.time);
+
+        let ranks: Vec<usize> = entries
+            .iter()
+            .map(|entry| {
+                entries
+                    .iter()
+                    .filter(|other| other.time < entry.time)
+                    .count()
+            })
+            .collect();
+
+        for (entry, rank) in entries.iter().zip(&ranks) {
+            let (num_wins, num_played, total_time) =
+                stats.entry(entry.username.as_str()).or_default();
+            *num_wins += i32::from(*rank == 0);
+            *num_played += 1;
+            *total_time += i64::from(entry.time);
+        }
+
+        let rating_of = |ratings: &HashMap<&str, S::Rating>, username: &str| {
+            ratings
+                .get(username)
+                .copied()
+                .unwrap_or_else(|| system.initial_rating())
+        };
+
+        if entries.len() >= 2 {
+            let prior_ratings: Vec<S::Rating> = entries
+                .iter()
+                .map(|entry| rating_of(&ratings, &entry.username))
+                .collect();
+
+            for (entry, rating) in entries.iter().zip(system.rate(&prior_ratings, &ranks)) {
+                ratings.insert(entry.username.as_str(), rating);
+            }
+        }
+
+        history.extend(entries.iter().map(|entry| {
+            let rating = rating_of(&ratings, &entry.username);
+            let (mu, sigma) = system.mu_sigma(&rating);
+            RatingSnapshot {
+                date: date.to_string(),
+                username: entry.username.clone(),
+                mu,
+                sigma,
+                elo: system.elo(&rating),
+            }
+        }));
+    }
+
+    let mut leaderboard: Vec<LeaderboardEntry> = stats
+        .into_iter()
+        .map(|(username, (num_wins, num_played, total_time))| {
+            let rating = ratings
+                .get(username)
+                .copied()
+                .unwrap_or_else(|| system.initial_rating());
+            let (mu, sigma) = system.mu_sigma(&rating);
+            LeaderboardEntry {
+                username: username.to_string(),
+                mu,
+                sigma,
+                average_time: total_time as f64 / num_played as f64,
+                num_wins,
+                num_played,
+                elo: system.elo(&rating),
+            }
+        })
+        .collect();
+
+    leaderboard.sort_by(|a, b| b.elo.total_cmp(&a.elo).then(a.username.cmp(&b.username)));
+
+    (leaderboard, history)
+}
+
+/// Builds every player's results against each other player in a crossword, for rating systems that only
+/// support one-on-one matches.
+fn pairwise_outcomes<R: Copy>(ratings: &[R], ranks: &[usize], player: usize) -> Vec<(R, Outcomes)> {
+    ratings
+        .iter()
+        .zip(ranks)
+        .enumerate()
+        .filter(|(opponent, _)| *opponent != player)
+        .map(|(_, (rating, rank))| {
+            let outcome = match ranks[player].cmp(rank) {
+                Ordering::Less => Outcomes::WIN,
+                Ordering::Greater => Outcomes::LOSS,
+                Ordering::Equal => Outcomes::DRAW,
+            };
+            (*rating, outcome)
+        })
+        .collect()
+}
+
+/// TrueSkill, matching the configuration of the `trueskill` Python package the leaderboard was originally
+/// computed with.
+pub struct TrueSkill;
+
+/// A player's TrueSkill rating.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rating {
+    pub mu: f64,
+    pub sigma: f64,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self {
+            mu: MU,
+            sigma: SIGMA,
+        }
+    }
+}
+
+impl RatingSystem for TrueSkill {
+    type Rating = Rating;
+
+    fn initial_rating(&self) -> Rating {
+        Rating::default()
+    }
+
+    fn rate(&self, ratings: &[Rating], ranks: &[usize]) -> Vec<Rating> {
+        rate(ratings, ranks)
+    }
+
+    fn mu_sigma(&self, rating: &Rating) -> (f64, f64) {
+        (rating.mu, rating.sigma)
+    }
+
+    fn elo(&self, rating: &Rating) -> f64 {
+        (rating.mu - 3.0 * rating.sigma) * 60.0
+    }
+
+    fn win_probability(&self, player: &Rating, opponent: &Rating) -> f64 {
+        trueskill::expected_score(
+            &TrueSkillRating {
+                rating: player.mu,
+                uncertainty: player.sigma,
+            },
+            &TrueSkillRating {
+                rating: opponent.mu,
+                uncertainty: opponent.sigma,
+            },
+            &TrueSkillConfig {
+                draw_probability: DRAW_PROBABILITY,
+                beta: BETA,
+                default_dynamics: TAU,
+            },
+        )
+        .0
+    }
+}
+
+/// Glicko-2, treating each crossword as a rating period in which every player faces every other player.
+pub struct Glicko2;
+
+impl RatingSystem for Glicko2 {
+    type Rating = Glicko2Rating;
+
+    fn initial_rating(&self) -> Glicko2Rating {
+        Glicko2Rating::new()
+    }
+
+    fn rate(&self, ratings: &[Glicko2Rating], ranks: &[usize]) -> Vec<Glicko2Rating> {
+        (0..ratings.len())
+            .map(|player| {
+                glicko2::glicko2_rating_period(
+                    &ratings[player],
+                    &pairwise_outcomes(ratings, ranks, player),
+                    &Glicko2Config::new(),
+                )
+            })
+            .collect()
+    }
+
+    fn mu_sigma(&self, rating: &Glicko2Rating) -> (f64, f64) {
+        (rating.rating, rating.deviation)
+    }
+
+    fn elo(&self, rating: &Glicko2Rating) -> f64 {
+        rating.rating - 2.0 * rating.deviation
+    }
+
+    fn win_probability(&self, player: &Glicko2Rating, opponent: &Glicko2Rating) -> f64 {
+        glicko2::expected_score(player, opponent).0
+    }
+}
+
+/// Weng-Lin (the OpenSkill model), which natively supports free-for-all games. It uses the same scale as
+/// TrueSkill, so its ELO is computed the same way.
+pub struct WengLin;
+
+impl RatingSystem for WengLin {
+    type Rating = WengLinRating;
+
+    fn initial_rating(&self) -> WengLinRating {
+        WengLinRating::new()
+    }
+
+    fn rate(&self, ratings: &[WengLinRating], ranks: &[usize]) -> Vec<WengLinRating> {
+        let teams: Vec<[WengLinRating; 1]> = ratings.iter().map(|rating| [*rating]).collect();
+        let teams_and_ranks: Vec<(&[WengLinRating], MultiTeamOutcome)> = teams
+            .iter()
+            .zip(ranks)
+            .map(|(team, rank)| (&team[..], MultiTeamOutcome::new(rank + 1)))
+            .collect();
+
+        weng_lin::weng_lin_multi_team(&teams_and_ranks, &WengLinConfig::new())
+            .into_iter()
+            .map(|team| team[0])
+            .collect()
+    }
+
+    fn mu_sigma(&self, rating: &WengLinRating) -> (f64, f64) {
+        (rating.rating, rating.uncertainty)
+    }
+
+    fn elo(&self, rating: &WengLinRating) -> f64 {
+        (rating.rating - 3.0 * rating.uncertainty) * 60.0
+    }
+
+    fn win_probability(&self, player: &WengLinRating, opponent: &WengLinRating) -> f64 {
+        weng_lin::expected_score(player, opponent, &WengLinConfig::new()).0
+    }
+}
+
+/// Classic Elo, treating each crossword as a set of one-on-one games against every other player. The K-factor is
+/// split across those games so a single crossword moves a rating about as much as one chess game.
+pub struct Elo;
+
+impl RatingSystem for Elo {
+    type Rating = EloRating;
+
+    fn initial_rating(&self) -> EloRating {
+        EloRating::new()
+    }
+
+    fn rate(&self, ratings: &[EloRating], ranks: &[usize]) -> Vec<EloRating> {
+        let config = EloConfig {
+            k: EloConfig::new().k / (ratings.len() - 1) as f64,
+        };
+
+        (0..ratings.len())
+            .map(|player| {
+                elo::elo_rating_period(
+                    &ratings[player],
+                    &pairwise_outcomes(ratings, ranks, player),
+                    &config,
+                )
+            })
+            .collect()
+    }
+
+    fn mu_sigma(&self, rating: &EloRating) -> (f64, f64) {
+        (rating.rating, 0.0)
+    }
+
+    fn elo(&self, rating: &EloRating) -> f64 {
+        rating.rating
+    }
+
+    fn win_probability(&self, player: &EloRating, opponent: &EloRating) -> f64 {
+        elo::expected_score(player, opponent).0
+    }
+}
+
+/// Updates the TrueSkill ratings of every player in a free-for-all crossword.
+///
+/// This is a port of `trueskill.rate` with one player per team, so ratings match the ones the original Python
+/// pipeline produced. It runs expectation propagation over the TrueSkill factor graph: each player's skill
+/// produces a noisy performance, and adjacent performances (in rank order) are constrained to differ by more than
+/// the draw margin, or by less than it for ties.
+///
+/// # Arguments
+///
+/// * `ratings` - The players' ratings before the crossword, sorted by rank.
+/// * `ranks` - The zero-based rank of each player, in ascending order. Equal ranks are ties.
+///
+/// # Returns
+///
+/// A vector containing the updated rating of each player, in the same order as `ratings`.
+pub fn rate(ratings: &[Rating], ranks: &[usize]) -> Vec<Rating> {
+    let n = ratings.len();
+    let draw_margin = ppf((DRAW_PROBABILITY + 1.0) / 2.0) * SQRT_2 * BETA;
+
+    let mut graph = FactorGraph::default();
+    let rating_vars: Vec<usize> = (0..n).map(|_| graph.add_variable()).collect();
+    let perf_vars: Vec<usize> = (0..n).map(|_| graph.add_variable()).collect();
+    let team_perf_vars: Vec<usize> = (0..n).map(|_| graph.add_variable()).collect();
+    let team_diff_vars: Vec<usize> = (1..n).map(|_| graph.add_variable()).collect();
+
+    let prior_factors: Vec<usize> = (0..n).map(|_| graph.add_factor()).collect();
+    let likelihood_factors: Vec<usize> = (0..n).map(|_| graph.add_factor()).collect();
+    let team_perf_factors: Vec<usize> = (0..n).map(|_| graph.add_factor()).collect();
+    let team_diff_factors: Vec<usize> = (1..n).map(|_| graph.add_factor()).collect();
+    let truncate_factors: Vec<usize> = (1..n).map(|_| graph.add_factor()).collect();
+
+    let team_diff = |i: usize| SumFactor {
+        id: team_diff_factors[i],
+        sum: team_diff_vars[i],
+        terms: vec![team_perf_vars[i], team_perf_vars[i + 1]],
+        coeffs: vec![1.0, -1.0],
+    };
+    let team_perf = |i: usize| SumFactor {
+        id: team_perf_factors[i],
+        sum: team_perf_vars[i],
+        terms: vec![perf_vars[i]],
+        coeffs: vec![1.0],
+    };
+
+    for i in 0..n {
+        let prior = Gaussian::new((
+            ratings[i].mu,
+            (ratings[i].sigma.powi(2) + TAU.powi(2)).sqrt(),
+        ));
+        graph.update_value(rating_vars[i], prior_factors[i], prior);
+    }
+    for i in 0..n {
+        graph.likelihood_pass(likelihood_factors[i], rating_vars[i], perf_vars[i]);
+    }
+    for i in 0..n {
+        graph.sum_down(&team_perf(i));
+    }
+
+    let truncate = |graph: &mut FactorGraph, i: usize| {
+        graph.truncate_up(
+            truncate_factors[i],
+            team_diff_vars[i],
+            draw_margin,
+            ranks[i] == ranks[i + 1],
+        )
+    };
+
+    for _ in 0..MAX_ITERATIONS {
+        let delta = if n == 2 {
+            graph.sum_down(&team_diff(0));
+            truncate(&mut graph, 0)
+        } else {
+            let mut delta: f64 = 0.0;
+            for i in 0..n - 2 {
+                graph.sum_down(&team_diff(i));
+                delta = delta.max(truncate(&mut graph, i));
+                graph.sum_up(&team_diff(i), 1);
+            }
+            for i in (1..n - 1).rev() {
+                graph.sum_down(&team_diff(i));
+                delta = delta.max(truncate(&mut graph, i));
+                graph.sum_up(&team_diff(i), 0);
+            }
+            delta
+        };
+
+        if delta <= MIN_DELTA {
+            break;
+        }
+    }
+
+    graph.sum_up(&team_diff(0), 0);
+    graph.sum_up(&team_diff(n - 2), 1);
+    for i in 0..n {
+        graph.sum_up(&team_perf(i), 0);
+    }
+    for i in 0..n {
+        graph.likelihood_pass(likelihood_factors[i], perf_vars[i], rating_vars[i]);
+    }
+
+    rating_vars
+        .iter()
+        .map(|&var| {
+            let value = graph.variables[var].value;
+            Rating {
+                mu: value.mu(),
+                sigma: value.sigma(),
+            }
+        })
+        .collect()
+}
+
+/// Predicts where each player will finish in a crossword they all play.
+///
+/// Each player's performance is drawn from their skill plus the same noise `rate` assumes, and the chance of each
+/// finishing position is found by integrating over one player's performance the chance that exactly k of the others
+/// beat it. Ties are ignored.
+///
+/// # Arguments
+///
+/// * `ratings` - The players' TrueSkill ratings.
+///
+/// # Returns
+///
+/// A vector containing, for each player in the same order as `ratings`, the probability of finishing in each
+/// position, first place first.
+pub fn predict_finishing_order(ratings: &[Rating]) -> Vec<Vec<f64>> {
+    let n = ratings.len();
+    let performances: Vec<(f64, f64)> = ratings
+        .iter()
+        .map(|rating| (rating.mu, (rating.sigma.powi(2) + BETA.powi(2)).sqrt()))
+        .collect();
+    let (Some(low), Some(high)) = (
+        performances
+            .iter()
+            .map(|(mu, sigma)| mu - 6.0 * sigma)
+            .reduce(f64::min),
+        performances
+            .iter()
+            .map(|(mu, sigma)| mu + 6.0 * sigma)
+            .reduce(f64::max),
+    ) else {
+        return Vec::new();
+    };
+    let step = (high - low) / PREDICTION_STEPS as f64;
+
+    performances
+        .iter()
+        .enumerate()
+        .map(|(i, &(mu, sigma))| {
+            let mut rank_probabilities = vec![0.0; n];
+            for s in 0..PREDICTION_STEPS {
+                let x = low + (s as f64 + 0.5) * step;
+                let density = pdf((x - mu) / sigma) / sigma * step;
+
+                // beaten_by[k] is the probability that exactly k of the other players outperform x.
+                let mut beaten_by = vec![0.0; n];
+                beaten_by[0] = 1.0;
+                for (j, &(other_mu, other_sigma)) in performances.iter().enumerate() {
+                    if j == i {
+                        continue;
+                    }
+                    let beats = 1.0 - cdf((x - other_mu) / other_sigma);
+                    for k in (0..n).rev() {
+                        let carried = if k > 0 { beaten_by[k - 1] * beats } else { 0.0 };
+                        beaten_by[k] = beaten_by[k] * (1.0 - beats) + carried;
+                    }
+                }
+
+                for (probability, beaten) in rank_probabilities.iter_mut().zip(&beaten_by) {
+                    *probability += density * beaten;
+                }
+            }
+
+            let total: f64 = rank_probabilities.iter().sum();
+            rank_probabilities
+                .into_iter()
+                .map(|probability| probability / total)
+                .collect()
+        })
+        .collect()
+}
+
+/// A Gaussian distribution in natural parameters: precision `pi` and precision-adjusted mean `tau`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Gaussian {
+    pi: f64,
+    tau: f64,
+}
+
+impl Gaussian {
+    fn new((mu, sigma): (f64, f64)) -> Self {
+        let pi = sigma.powi(-2);
+        Self { pi, tau: pi * mu }
+    }
+
+    fn mu(&self) -> f64 {
+        if self.pi == 0.0 {
+            0.0
+        } else {
+            self.tau / self.pi
+        }
+    }
+
+    fn sigma(&self) -> f64 {
+        if self.pi == 0.0 {
+            f64::INFINITY
+        } else {
+            (1.0 / self.pi).sqrt()
+        }
+    }
+}
+
+impl Mul for Gaussian {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self {
+            pi: self.pi + other.pi,
+            tau: self.tau + other.tau,
+        }
+    }
+}
+
+impl Div for Gaussian {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        Self {
+            pi: self.pi - other.pi,
+            tau: self.tau - other.tau,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Variable {
+    value: Gaussian,
+    messages: HashMap<usize, Gaussian>,
+}
+
+impl Variable {
+    fn message(&self, factor: usize) -> Gaussian {
+        self.messages.get(&factor).copied().unwrap_or_default()
+    }
+
+    fn set(&mut self, value: Gaussian) -> f64 {
+        let pi_delta = (self.value.pi - value.pi).abs();
+        let delta = if pi_delta.is_infinite() {
+            0.0
+        } else {
+            (self.value.tau - value.tau).abs().max(pi_delta.sqrt())
+        };
+        self.value = value;
+        delta
+    }
+}
+
+/// A factor `sum = coeffs[0] * terms[0] + coeffs[1] * terms[1] + ...`.
+struct SumFactor {
+    id: usize,
+    sum: usize,
+    terms: Vec<usize>,
+    coeffs: Vec<f64>,
+}
+
+#[derive(Default)]
+struct FactorGraph {
+    variables: Vec<Variable>,
+    num_factors: usize,
+}
+
+impl FactorGraph {
+    fn add_variable(&mut self) -> usize {
+        self.variables.push(Variable::default());
+        self.variables.len() - 1
+    }
+
+    fn add_factor(&mut self) -> usize {
+        self.num_factors += 1;
+        self.num_factors - 1
+    }
+
+    fn update_message(&mut self, var: usize, factor: usize, message: Gaussian) -> f64 {
+        let variable = &mut self.variables[var];
+        let old_message = variable.message(factor);
+        variable.messages.insert(factor, message);
+        let value = variable.value / old_message * message;
+        variable.set(value)
+    }
+
+    fn update_value(&mut self, var: usize, factor: usize, value: Gaussian) -> f64 {
+        let variable = &mut self.variables[var];
+        let old_message = variable.message(factor);
+        variable
+            .messages
+            .insert(factor, value * old_message / variable.value);
+        variable.set(value)
+    }
+
+    /// Passes a message through the performance noise factor, from `from` (skill or performance) to `to`.
+    fn likelihood_pass(&mut self, factor: usize, from: usize, to: usize) -> f64 {
+        let message = self.variables[from].value / self.variables[from].message(factor);
+        let a = 1.0 / (1.0 + BETA.powi(2) * message.pi);
+        self.update_message(
+            to,
+            factor,
+            Gaussian {
+                pi: a * message.pi,
+                tau: a * message.tau,
+            },
+        )
+    }
+
+    fn sum_down(&mut self, factor: &SumFactor) -> f64 {
+        self.sum_update(factor.id, factor.sum, &factor.terms, &factor.coeffs)
+    }
+
+    fn sum_up(&mut self, factor: &SumFactor, index: usize) -> f64 {
+        let coeff = factor.coeffs[index];
+        let coeffs: Vec<f64> = factor
+            .coeffs
+            .iter()
+            .enumerate()
+            .map(|(i, c)| if i == index { 1.0 / coeff } else { -c / coeff })
+            .collect();
+        let mut vals = factor.terms.clone();
+        vals[index] = factor.sum;
+        self.sum_update(factor.id, factor.terms[index], &vals, &coeffs)
+    }
+
+    fn sum_update(&mut self, factor: usize, var: usize, vals: &[usize], coeffs: &[f64]) -> f64 {
+        let mut pi_inv: f64 = 0.0;
+        let mut mu = 0.0;
+        for (&val, coeff) in vals.iter().zip(coeffs) {
+            let div = self.variables[val].value / self.variables[val].message(factor);
+            mu += coeff * div.mu();
+            if pi_inv.is_infinite() {
+                continue;
+            }
+            if div.pi == 0.0 {
+                pi_inv = f64::INFINITY;
+            } else {
+                pi_inv += coeff.powi(2) / div.pi;
+            }
+        }
+        let pi = 1.0 / pi_inv;
+        self.update_message(var, factor, Gaussian { pi, tau: pi * mu })
+    }
+
+    fn truncate_up(&mut self, factor: usize, var: usize, draw_margin: f64, is_draw: bool) -> f64 {
+        let div = self.variables[var].value / self.variables[var].message(factor);
+        let sqrt_pi = div.pi.sqrt();
+        let diff = div.tau / sqrt_pi;
+        let margin = draw_margin * sqrt_pi;
+        let (v, w) = if is_draw {
+            (v_draw(diff, margin), w_draw(diff, margin))
+        } else {
+            (v_win(diff, margin), w_win(diff, margin))
+        };
+        let denom = 1.0 - w;
+        self.update_value(
+            var,
+            factor,
+            Gaussian {
+                pi: div.pi / denom,
+                tau: (div.tau + sqrt_pi * v) / denom,
+            },
+        )
+    }
+}
+
+fn v_win(diff: f64, draw_margin: f64) -> f64 {
+    let x = diff - draw_margin;
+    let denom = cdf(x);
+    if denom == 0.0 {
+        -x
+    } else {
+        pdf(x) / denom
+    }
+}
+
+fn w_win(diff: f64, draw_margin: f64) -> f64 {
+    let x = diff - draw_margin;
+    let v = v_win(diff, draw_margin);
+    (v * (v + x)).clamp(f64::EPSILON, 1.0 - f64::EPSILON)
+}
+
+fn v_draw(diff: f64, draw_margin: f64) -> f64 {
+    let abs_diff = diff.abs();
+    let (a, b) = (draw_margin - abs_diff, -draw_margin - abs_diff);
+    let denom = cdf(a) - cdf(b);
+    let numer = pdf(b) - pdf(a);
+    let v = if denom == 0.0 { a } else { numer / denom };
+    if diff < 0.0 {
+        -v
+    } else {
+        v
+    }
+}
+
+fn w_draw(diff: f64, draw_margin: f64) -> f64 {
+    let abs_diff = diff.abs();
+    let (a, b) = (draw_margin - abs_diff, -draw_margin - abs_diff);
+    let denom = cdf(a) - cdf(b);
+    if denom == 0.0 {
+        return 1.0 - f64::EPSILON;
+    }
+    let v = v_draw(abs_diff, draw_margin);
+    v.powi(2) + (a * pdf(a) - b * pdf(b)) / denom
+}
+
+/// Complementary error function, using the same approximation as the `trueskill` Python package.
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + z / 2.0);
+    let r = t
+        * (-z * z - 1.26551223
+            + t * (1.00002368
+                + t * (0.37409196
+                    + t * (0.09678418
+                        + t * (-0.18628806
+                            + t * (0.27886807
+                                + t * (-1.13520398
+                                    + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277)))))))))
+            .exp();
+    if x < 0.0 {
+        2.0 - r
+    } else {
+        r
+    }
+}
+
+/// Inverse of `erfc`.
+fn ierfc(y: f64) -> f64 {
+    if y >= 2.0 {
+        return -100.0;
+    }
+    if y <= 0.0 {
+        return 100.0;
+    }
+    let zero_point = y < 1.0;
+    let y = if zero_point { y } else { 2.0 - y };
+    let t = (-2.0 * (y / 2.0).ln()).sqrt();
+    let mut x =
+        -FRAC_1_SQRT_2 * ((2.30753 + t * 0.27061) / (1.0 + t * (0.99229 + t * 0.04481)) - t);
+    for _ in 0..2 {
+        let err = erfc(x) - y;
+        x += err / (FRAC_2_SQRT_PI * (-(x.powi(2))).exp() - x * err);
+    }
+    if zero_point {
+        x
+    } else {
+        -x
+    }
+}
+
+/// Cumulative distribution function of the standard normal distribution.
+pub fn cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / SQRT_2)
+}
+
+/// Probability density function of the standard normal distribution.
+pub fn pdf(x: f64) -> f64 {
+    (-(x.powi(2)) / 2.0).exp() / (2.0 * PI).sqrt()
+}
+
+/// Inverse of `cdf`.
+fn ppf(x: f64) -> f64 {
+    -SQRT_2 * ierfc(2.0 * x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_rating(rating: Rating, mu: f64, sigma: f64) {
+        assert!(
+            (rating.mu - mu).abs() < 1e-3 && (rating.sigma - sigma).abs() < 1e-3,
+            "expected ({mu}, {sigma}), got ({}, {})",
+            rating.mu,
+            rating.sigma
+        );
+    }
+
+    fn result(date: &str, username: &str, time: i32) -> ResultEntry {
+        ResultEntry {
+            date: date.to_string(),
+            time,
+            username: username.to_string(),
+            rank: 0,
+        }
+    }
+
+    #[test]
+    fn test_rate_two_players() {
+        let ratings = rate(&[Rating::default(), Rating::default()], &[0, 1]);
+
+        assert_rating(ratings[0], 29.396, 7.171);
+        assert_rating(ratings[1], 20.604, 7.171);
+    }
+
+    #[test]
+    fn test_rate_two_players_draw() {
+        let ratings = rate(&[Rating::default(), Rating::default()], &[0, 0]);
+
+        assert_rating(ratings[0], 25.000, 6.458);
+        assert_rating(ratings[1], 25.000, 6.458);
+    }
+
+    #[test]
+    fn test_rate_three_players() {
+        let ratings = rate(&[Rating::default(); 3], &[0, 1, 2]);
+
+        assert_rating(ratings[0], 31.675, 6.656);
+        assert_rating(ratings[1], 25.000, 6.208);
+        assert_rating(ratings[2], 18.325, 6.656);
+    }
+
+    #[test]
+    fn test_compute_leaderboard() {
+        let results = vec![
+            result("2026-10-02", "bob", 50),
+            result("2026-10-01", "alice", 40),
+            result("2026-10-01", "bob", 45),
+            result("2026-10-01", "carol", 30),
+            result("2026-10-02", "alice", 40),
+            result("2026-10-02", "carol", 40),
+            result("2026-10-03", "carol", 50),
+        ];
+
+        let leaderboard = compute_leaderboard(&results, &TrueSkill);
+
+        let usernames: Vec<&str> = leaderboard
+            .iter()
+            .map(|entry| entry.username.as_str())
+            .collect();
+        assert_eq!(usernames, vec!["carol", "alice", "bob"]);
+
+        let carol = &leaderboard[0];
+        assert_eq!(carol.num_played, 3);
+        assert_eq!(carol.num_wins, 3);
+        assert_eq!(carol.average_time, 40.0);
+
+        let alice = &leaderboard[1];
+        assert_eq!(alice.num_played, 2);
+        assert_eq!(alice.num_wins, 1);
+
+        let bob = &leaderboard[2];
+        assert_eq!(bob.num_wins, 0);
+        assert_eq!(
+            bob.elo,
+            TrueSkill.elo(&Rating {
+                mu: bob.mu,
+                sigma: bob.sigma
+            })
+        );
+    }
+
+    #[test]
+    fn test_compute_leaderboard_with_history() {
+        let results = vec![
+            result("2026-10-02", "alice", 40),
+            result("2026-10-01", "alice", 40),
+            result("2026-10-01", "bob", 45),
+            result("2026-10-03", "bob", 50),
+        ];
+
+        let (leaderboard, history) = compute_leaderboard_with_history(&results, &TrueSkill);
+
+        let snapshots: Vec<(&str, &str)> = history
+            .iter()
+            .map(|snapshot| (snapshot.date.as_str(), snapshot.username.as_str()))
+            .collect();
+        assert_eq!(
+            snapshots,
+            vec![
+                ("2026-10-01", "alice"),
+                ("2026-10-01", "bob"),
+                ("2026-10-02", "alice"),
+                ("2026-10-03", "bob"),
+            ]
+        );
+        assert!(history[0].elo > history[1].elo);
+        assert_eq!(history[0].mu, history[2].mu);
+
+        let bob = leaderboard
+            .iter()
+            .find(|entry| entry.username == "bob")
+            .unwrap();
+        assert_eq!(bob.elo, history[3].elo);
+    }
+
+    #[test]
+    fn test_compute_leaderboard_with_no_results() {
+        for kind in RatingSystemKind::ALL {
+            assert!(kind.compute_leaderboard(&[]).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_every_rating_system_ranks_the_fastest_player_first() {
+        let results = vec![
+            result("2026-10-01", "alice", 40),
+            result("2026-10-01", "bob", 45),
+            result("2026-10-01", "carol", 30),
+            result("2026-10-02", "alice", 35),
+            result("2026-10-02", "bob", 50),
+            result("2026-10-02", "carol", 30),
+        ];
+
+        for kind in RatingSystemKind::ALL {
+            let usernames: Vec<String> = kind
+                .compute_leaderboard(&results)
+                .into_iter()
+                .map(|entry| entry.username)
+                .collect();
+            assert_eq!(usernames, vec!["carol", "alice", "bob"], "{}", kind.label());
+        }
+    }
+
+    #[test]
+    fn test_win_probability_favours_the_higher_rating() {
+        let strong = Rating {
+            mu: 30.0,
+            sigma: 2.0,
+        };
+        let weak = Rating {
+            mu: 20.0,
+            sigma: 2.0,
+        };
+
+        assert!(TrueSkill.win_probability(&strong, &weak) > 0.5);
+        assert!(
+            Glicko2.win_probability(
+                &Glicko2Rating {
+                    rating: 1700.0,
+                    ..Glicko2Rating::new()
+                },
+                &Glicko2Rating::new()
+            ) > 0.5
+        );
+        assert!(Elo.win_probability(&EloRating { rating: 1100.0 }, &EloRating::new()) > 0.5);
+    }
+
+    #[test]
+    fn test_predict_finishing_order() {
+        let even = predict_finishing_order(&[Rating::default(); 3]);
+        for rank_probabilities in &even {
+            for probability in rank_probabilities {
+                assert!((probability - 1.0 / 3.0).abs() < 1e-3);
+            }
+        }
+
+        let strong = Rating {
+            mu: 30.0,
+            sigma: 2.0,
+        };
+        let weak = Rating {
+            mu: 20.0,
+            sigma: 2.0,
+        };
+        let pair = predict_finishing_order(&[strong, weak]);
+        assert!((pair[0][0] - TrueSkill.win_probability(&strong, &weak)).abs() < 1e-3);
+        assert!((pair[0][0] + pair[1][0] - 1.0).abs() < 1e-6);
+
+        let field = predict_finishing_order(&[weak, strong, Rating::default()]);
+        assert!(field[1][0] > field[2][0] && field[2][0] > field[0][0]);
+        assert!(field[0][2] > field[0][0]);
+        assert!(predict_finishing_order(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_rating_system_kind_ids_round_trip() {
+        for kind in RatingSystemKind::ALL {
+            assert_eq!(RatingSystemKind::from_id(kind.id()), Some(kind));
+        }
+        assert_eq!(RatingSystemKind::from_id("chess"), None);
+    }
+}
@@ -0,0 +1,680 @@
+use chrono::NaiveDate;
+use postgrest::Postgrest;
+use thiserror::Error;
+
+use crate::d1_store::D1Store;
+use crate::database;
+use crate::models::{
+    AuditEntry, FlaggedResult, LeaderboardEntry, ModerationStatus, PodiumFilter, PuzzleDifficulty,
+    RatingSnapshot, ResultEntry, ResultScore, SeasonStanding, UserData,
+};
+use crate::util::SubmissionError;
+
+/// Everything that can go wrong while reading from or writing to a `ResultStore`.
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error(transparent)]
+    Submission(#[from] SubmissionError),
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Couldn't parse response: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("D1 error: {0}")]
+    D1(#[from] worker::Error),
+    #[error("A batch of statements failed: {0}")]
+    Batch(String),
+    #[error("Stored date is invalid: {0}")]
+    InvalidDate(#[from] chrono::ParseError),
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+    #[error("There are no results yet")]
+    NoResults,
+    #[error("Unknown leaderboard table: {0}")]
+    UnknownLeaderboard(String),
+}
+
+/// Storage for crossword results and the leaderboards and rating history computed from them.
+///
+/// Handlers only talk to storage through this trait, so they can run against Supabase in production and against
+/// an in-memory store in tests.
+pub trait ResultStore {
+    /// Fetches the results for a given date, sorted by time.
+    async fn fetch_results(&self, date: &str) -> Result<Vec<ResultEntry>, StoreError>;
+
+    /// Fetches every result, sorted by date and then time.
+    async fn fetch_all_results(&self) -> Result<Vec<ResultEntry>, StoreError>;
+
+    /// Fetches every result on or after a given date, sorted by date and then time.
+    async fn fetch_results_since(&self, date: &str) -> Result<Vec<ResultEntry>, StoreError>;
+
+    /// Inserts new results for a given date and re-ranks every result recorded for that date.
+    ///
+    /// Returns the inserted results with their ranks, or a `SubmissionError` if a user already has a result for
+    /// the date.
+    async fn insert_results(
+        &self,
+        date: &str,
+        new_entries: Vec<ResultEntry>,
+    ) -> Result<Vec<ResultEntry>, StoreError>;
+
+    /// Changes a user's time for a date and re-ranks every result recorded for that date.
+    async fn update_result_time(
+        &self,
+        date: &str,
+        username: &str,
+        time: i32,
+    ) -> Result<(), StoreError>;
+
+    /// Deletes a user's result for a date and re-ranks the remaining results for that date.
+    async fn delete_result(&self, date: &str, username: &str) -> Result<(), StoreError>;
+
+    /// Moves every result recorded for `from` to `to`, along with their flags, rating history and scores.
+    async fn rename_user(&self, from: &str, to: &str) -> Result<(), StoreError>;
+
+    /// Fetches every audit log entry, most recent first.
+    async fn fetch_audit_log(&self) -> Result<Vec<AuditEntry>, StoreError>;
+
+    /// Adds an entry to the audit log.
+    async fn insert_audit_entry(&self, entry: &AuditEntry) -> Result<(), StoreError>;
+
+    /// Fetches the most recent date with at least one result.
+    async fn fetch_most_recent_crossword_date(&self) -> Result<NaiveDate, StoreError>;
+
+    /// Fetches the usernames on the all-time leaderboard, sorted by ELO in descending order.
+    async fn fetch_usernames_sorted_by_elo(&self) -> Result<Vec<String>, StoreError>;
+
+    /// Fetches the fastest results matching `filter`, sorted by time, leaving out results with a flag that hasn't
+    /// been approved.
+    async fn fetch_podium_data(
+        &self,
+        filter: &PodiumFilter,
+    ) -> Result<Vec<ResultEntry>, StoreError>;
+
+    /// Fetches every result recorded for a user, sorted by time.
+    async fn fetch_user_data(&self, username: &str) -> Result<UserData, StoreError>;
+
+    /// Fetches a stored leaderboard, sorted by ELO in descending order.
+    async fn fetch_leaderboard_from_db(
+        &self,
+        db_name: &str,
+    ) -> Result<Vec<LeaderboardEntry>, StoreError>;
+
+    /// Replaces the contents of a stored leaderboard.
+    async fn replace_leaderboard_in_db(
+        &self,
+        db_name: &str,
+        entries: &[LeaderboardEntry],
+    ) -> Result<(), StoreError>;
+
+    /// Fetches the rating history for a user, sorted by date.
+    async fn fetch_rating_history(&self, username: &str)
+        -> Result<Vec<RatingSnapshot>, StoreError>;
+
+    /// Replaces the stored rating history.
+    async fn replace_rating_history_in_db(
+        &self,
+        history: &[RatingSnapshot],
+    ) -> Result<(), StoreError>;
+
+    /// Fetches the stored final standings of a season, sorted by rank.
+    async fn fetch_season_standings(
+        &self,
+        season_id: &str,
+    ) -> Result<Vec<SeasonStanding>, StoreError>;
+
+    /// Fetches the top three of every season with stored standings, sorted by season and then rank.
+    async fn fetch_season_podiums(&self) -> Result<Vec<SeasonStanding>, StoreError>;
+
+    /// Replaces the stored final standings of every season.
+    async fn replace_season_standings_in_db(
+        &self,
+        standings: &[SeasonStanding],
+    ) -> Result<(), StoreError>;
+
+    /// Fetches the stored difficulty of every puzzle, hardest first.
+    async fn fetch_puzzle_difficulties(&self) -> Result<Vec<PuzzleDifficulty>, StoreError>;
+
+    /// Fetches the stored difficulty of the puzzle for a date, or `None` if it hasn't been scored.
+    async fn fetch_puzzle_difficulty(
+        &self,
+        date: &str,
+    ) -> Result<Option<PuzzleDifficulty>, StoreError>;
+
+    /// Fetches the stored difficulty of every puzzle on or after a given date, sorted by date.
+    async fn fetch_puzzle_difficulties_since(
+        &self,
+        date: &str,
+    ) -> Result<Vec<PuzzleDifficulty>, StoreError>;
+
+    /// Replaces the stored difficulty of every puzzle.
+    async fn replace_puzzle_difficulties_in_db(
+        &self,
+        difficulties: &[PuzzleDifficulty],
+    ) -> Result<(), StoreError>;
+
+    /// Fetches the stored scores of every result for a date.
+    async fn fetch_result_scores(&self, date: &str) -> Result<Vec<ResultScore>, StoreError>;
+
+    /// Fetches a user's stored scores with the best field scores, best first, leaving out unscored results.
+    async fn fetch_best_result_scores(
+        &self,
+        username: &str,
+        limit: usize,
+    ) -> Result<Vec<ResultScore>, StoreError>;
+
+    /// Fetches the stored scores matching `filter` with the best field scores, best first, leaving out unscored
+    /// results.
+    async fn fetch_relative_podium_data(
+        &self,
+        filter: &PodiumFilter,
+    ) -> Result<Vec<ResultScore>, StoreError>;
+
+    /// Replaces the stored scores of every result.
+    async fn replace_result_scores_in_db(&self, scores: &[ResultScore]) -> Result<(), StoreError>;
+
+    /// Fetches every flagged result, most recent date first.
+    async fn fetch_flagged_results(&self) -> Result<Vec<FlaggedResult>, StoreError>;
+
+    /// Adds results to the moderation queue.
+    async fn insert_flagged_results(&self, flags: &[FlaggedResult]) -> Result<(), StoreError>;
+
+    /// Sets the moderation status of a user's flagged result for a date.
+    async fn update_flag_status(
+        &self,
+        date: &str,
+        username: &str,
+        status: ModerationStatus,
+    ) -> Result<(), StoreError>;
+
+    /// Deletes a user's flagged result for a date, if there is one.
+    async fn delete_flagged_result(&self, date: &str, username: &str) -> Result<(), StoreError>;
+
+    /// Fetches a user's all-time TrueSkill `(mu, sigma)`, or `None` if they aren't on the all-time leaderboard.
+    async fn fetch_user_trueskill_from_db(
+        &self,
+        username: &str,
+    ) -> Result<Option<(f64, f64)>, StoreError>;
+}
+
+impl ResultStore for Postgrest {
+    async fn fetch_results(&self, date: &str) -> Result<Vec<ResultEntry>, StoreError> {
+        database::fetch_results(date, self).await
+    }
+
+    async fn fetch_all_results(&self) -> Result<Vec<ResultEntry>, StoreError> {
+        database::fetch_all_results(self).await
+    }
+
+    async fn fetch_results_since(&self, date: &str) -> Result<Vec<ResultEntry>, StoreError> {
+        database::fetch_results_since(date, self).await
+    }
+
+    async fn insert_results(
+        &self,
+        date: &str,
+        new_entries: Vec<ResultEntry>,
+    ) -> Result<Vec<ResultEntry>, StoreError> {
+        database::insert_results(date, new_entries, self).await
+    }
+
+    async fn update_result_time(
+        &self,
+        date: &str,
+        username: &str,
+        time: i32,
+    ) -> Result<(), StoreError> {
+        database::update_result_time(date, username, time, self).await
+    }
+
+    async fn delete_result(&self, date: &str, username: &str) -> Result<(), StoreError> {
+        database::delete_result(date, username, self).await
+    }
+
+    async fn rename_user(&self, from: &str, to: &str) -> Result<(), StoreError> {
+        database::rename_user(from, to, self).await
+    }
+
+    async fn fetch_audit_log(&self) -> Result<Vec<AuditEntry>, StoreError> {
+        database::fetch_audit_log(self).await
+    }
+
+    async fn insert_audit_entry(&self, entry: &AuditEntry) -> Result<(), StoreError> {
+        database::insert_audit_entry(entry, self).await
+    }
+
+    async fn fetch_most_recent_crossword_date(&self) -> Result<NaiveDate, StoreError> {
+        database::fetch_most_recent_crossword_date(self).await
+    }
+
+    async fn fetch_usernames_sorted_by_elo(&self) -> Result<Vec<String>, StoreError> {
+        database::fetch_usernames_sorted_by_elo(self).await
+    }
+
+    async fn fetch_podium_data(
+        &self,
+        filter: &PodiumFilter,
+    ) -> Result<Vec<ResultEntry>, StoreError> {
+        database::fetch_podium_data(filter, self).await
+    }
+
+    async fn fetch_user_data(&self, username: &str) -> Result<UserData, StoreError> {
+        database::fetch_user_data(username, self).await
+    }
+
+    async fn fetch_leaderboard_from_db(
+        &self,
+        db_name: &str,
+    ) -> Result<Vec<LeaderboardEntry>, StoreError> {
+        database::fetch_leaderboard_from_db(db_name, self).await
+    }
+
+    async fn replace_leaderboard_in_db(
+        &self,
+        db_name: &str,
+        entries: &[LeaderboardEntry],
+    ) -> Result<(), StoreError> {
+        database::replace_leaderboard_in_db(db_name, entries, self).await
+    }
+
+    async fn fetch_rating_history(
+        &self,
+        username: &str,
+    ) -> Result<Vec<RatingSnapshot>, StoreError> {
+        database::fetch_rating_history(username, self).await
+    }
+
+    async fn replace_rating_history_in_db(
+        &self,
+        history: &[RatingSnapshot],
+    ) -> Result<(), StoreError> {
+        database::replace_rating_history_in_db(history, self).await
+    }
+
+    async fn fetch_season_standings(
+        &self,
+        season_id: &str,
+    ) -> Result<Vec<SeasonStanding>, StoreError> {
+        database::fetch_season_standings(season_id, self).await
+    }
+
+    async fn fetch_season_podiums(&self) -> Result<Vec<SeasonStanding>, StoreError> {
+        database::fetch_season_podiums(self).await
+    }
+
+    async fn replace_season_standings_in_db(
+        &self,
+        standings: &[SeasonStanding],
+    ) -> Result<(), StoreError> {
+        database::replace_season_standings_in_db(standings, self).await
+    }
+
+    async fn fetch_puzzle_difficulties(&self) -> Result<Vec<PuzzleDifficulty>, StoreError> {
+        database::fetch_puzzle_difficulties(self).await
+    }
+
+    async fn fetch_puzzle_difficulty(
+        &self,
+        date: &str,
+    ) -> Result<Option<PuzzleDifficulty>, StoreError> {
+        database::fetch_puzzle_difficulty(date, self).await
+    }
+
+    async fn fetch_puzzle_difficulties_since(
+        &self,
+        date: &str,
+    ) -> Result<Vec<PuzzleDifficulty>, StoreError> {
+        database::fetch_puzzle_difficulties_since(date, self).await
+    }
+
+    async fn replace_puzzle_difficulties_in_db(
+        &self,
+        difficulties: &[PuzzleDifficulty],
+    ) -> Result<(), StoreError> {
+        database::replace_puzzle_difficulties_in_db(difficulties, self).await
+    }
+
+    async fn fetch_result_scores(&self, date: &str) -> Result<Vec<ResultScore>, StoreError> {
+        database::fetch_result_scores(date, self).await
+    }
+
+    async fn fetch_best_result_scores(
+        &self,
+        username: &str,
+        limit: usize,
+    ) -> Result<Vec<ResultScore>, StoreError> {
+        database::fetch_best_result_scores(username, limit, self).await
+    }
+
+    async fn fetch_relative_podium_data(
+        &self,
+        filter: &PodiumFilter,
+    ) -> Result<Vec<ResultScore>, StoreError> {
+        database::fetch_relative_podium_data(filter, self).await
+    }
+
+    async fn replace_result_scores_in_db(&self, scores: &[ResultScore]) -> Result<(), StoreError> {
+        database::replace_result_scores_in_db(scores, self).await
+    }
+
+    async fn fetch_flagged_results(&self) -> Result<Vec<FlaggedResult>, StoreError> {
+        database::fetch_flagged_results(self).await
+    }
+
+    async fn insert_flagged_results(&self, flags: &[FlaggedResult]) -> Result<(), StoreError> {
+        database::insert_flagged_results(flags, self).await
+    }
+
+    async fn update_flag_status(
+        &self,
+        date: &str,
+        username: &str,
+        status: ModerationStatus,
+    ) -> Result<(), StoreError> {
+        database::update_flag_status(date, username, status, self).await
+    }
+
+    async fn delete_flagged_result(&self, date: &str, username: &str) -> Result<(), StoreError> {
+        database::delete_flagged_result(date, username, self).await
+    }
+
+    async fn fetch_user_trueskill_from_db(
+        &self,
+        username: &str,
+    ) -> Result<Option<(f64, f64)>, StoreError> {
+        database::fetch_user_trueskill_from_db(username, self).await
+    }
+}
+
+/// The `ResultStore` selected by the `STORAGE_BACKEND` var.
+pub enum Store {
+    Supabase(Postgrest),
+    D1(D1Store),
+}
+
+impl ResultStore for Store {
+    async fn fetch_results(&self, date: &str) -> Result<Vec<ResultEntry>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_results(date).await,
+            Store::D1(store) => store.fetch_results(date).await,
+        }
+    }
+
+    async fn fetch_all_results(&self) -> Result<Vec<ResultEntry>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_all_results().await,
+            Store::D1(store) => store.fetch_all_results().await,
+        }
+    }
+
+    async fn fetch_results_since(&self, date: &str) -> Result<Vec<ResultEntry>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_results_since(date).await,
+            Store::D1(store) => store.fetch_results_since(date).await,
+        }
+    }
+
+    async fn insert_results(
+        &self,
+        date: &str,
+        new_entries: Vec<ResultEntry>,
+    ) -> Result<Vec<ResultEntry>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.insert_results(date, new_entries).await,
+            Store::D1(store) => store.insert_results(date, new_entries).await,
+        }
+    }
+
+    async fn update_result_time(
+        &self,
+        date: &str,
+        username: &str,
+        time: i32,
+    ) -> Result<(), StoreError> {
+        match self {
+            Store::Supabase(client) => client.update_result_time(date, username, time).await,
+            Store::D1(store) => store.update_result_time(date, username, time).await,
+        }
+    }
+
+    async fn delete_result(&self, date: &str, username: &str) -> Result<(), StoreError> {
+        match self {
+            Store::Supabase(client) => client.delete_result(date, username).await,
+            Store::D1(store) => store.delete_result(date, username).await,
+        }
+    }
+
+    async fn rename_user(&self, from: &str, to: &str) -> Result<(), StoreError> {
+        match self {
+            Store::Supabase(client) => client.rename_user(from, to).await,
+            Store::D1(store) => store.rename_user(from, to).await,
+        }
+    }
+
+    async fn fetch_audit_log(&self) -> Result<Vec<AuditEntry>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_audit_log().await,
+            Store::D1(store) => store.fetch_audit_log().await,
+        }
+    }
+
+    async fn insert_audit_entry(&self, entry: &AuditEntry) -> Result<(), StoreError> {
+        match self {
+            Store::Supabase(client) => client.insert_audit_entry(entry).await,
+            Store::D1(store) => store.insert_audit_entry(entry).await,
+        }
+    }
+
+    async fn fetch_most_recent_crossword_date(&self) -> Result<NaiveDate, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_most_recent_crossword_date().await,
+            Store::D1(store) => store.fetch_most_recent_crossword_date().await,
+        }
+    }
+
+    async fn fetch_usernames_sorted_by_elo(&self) -> Result<Vec<String>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_usernames_sorted_by_elo().await,
+            Store::D1(store) => store.fetch_usernames_sorted_by_elo().await,
+        }
+    }
+
+    async fn fetch_podium_data(
+        &self,
+        filter: &PodiumFilter,
+    ) -> Result<Vec<ResultEntry>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_podium_data(filter).await,
+            Store::D1(store) => store.fetch_podium_data(filter).await,
+        }
+    }
+
+    async fn fetch_user_data(&self, username: &str) -> Result<UserData, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_user_data(username).await,
+            Store::D1(store) => store.fetch_user_data(username).await,
+        }
+    }
+
+    async fn fetch_leaderboard_from_db(
+        &self,
+        db_name: &str,
+    ) -> Result<Vec<LeaderboardEntry>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_leaderboard_from_db(db_name).await,
+            Store::D1(store) => store.fetch_leaderboard_from_db(db_name).await,
+        }
+    }
+
+    async fn replace_leaderboard_in_db(
+        &self,
+        db_name: &str,
+        entries: &[LeaderboardEntry],
+    ) -> Result<(), StoreError> {
+        match self {
+            Store::Supabase(client) => client.replace_leaderboard_in_db(db_name, entries).await,
+            Store::D1(store) => store.replace_leaderboard_in_db(db_name, entries).await,
+        }
+    }
+
+    async fn fetch_rating_history(
+        &self,
+        username: &str,
+    ) -> Result<Vec<RatingSnapshot>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_rating_history(username).await,
+            Store::D1(store) => store.fetch_rating_history(username).await,
+        }
+    }
+
+    async fn replace_rating_history_in_db(
+        &self,
+        history: &[RatingSnapshot],
+    ) -> Result<(), StoreError> {
+        match self {
+            Store::Supabase(client) => client.replace_rating_history_in_db(history).await,
+            Store::D1(store) => store.replace_rating_history_in_db(history).await,
+        }
+    }
+
+    async fn fetch_season_standings(
+        &self,
+        season_id: &str,
+    ) -> Result<Vec<SeasonStanding>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_season_standings(season_id).await,
+            Store::D1(store) => store.fetch_season_standings(season_id).await,
+        }
+    }
+
+    async fn fetch_season_podiums(&self) -> Result<Vec<SeasonStanding>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_season_podiums().await,
+            Store::D1(store) => store.fetch_season_podiums().await,
+        }
+    }
+
+    async fn replace_season_standings_in_db(
+        &self,
+        standings: &[SeasonStanding],
+    ) -> Result<(), StoreError> {
+        match self {
+            Store::Supabase(client) => client.replace_season_standings_in_db(standings).await,
+            Store::D1(store) => store.replace_season_standings_in_db(standings).await,
+        }
+    }
+
+    async fn fetch_puzzle_difficulties(&self) -> Result<Vec<PuzzleDifficulty>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_puzzle_difficulties().await,
+            Store::D1(store) => store.fetch_puzzle_difficulties().await,
+        }
+    }
+
+    async fn fetch_puzzle_difficulty(
+        &self,
+        date: &str,
+    ) -> Result<Option<PuzzleDifficulty>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_puzzle_difficulty(date).await,
+            Store::D1(store) => store.fetch_puzzle_difficulty(date).await,
+        }
+    }
+
+    async fn fetch_puzzle_difficulties_since(
+        &self,
+        date: &str,
+    ) -> Result<Vec<PuzzleDifficulty>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_puzzle_difficulties_since(date).await,
+            Store::D1(store) => store.fetch_puzzle_difficulties_since(date).await,
+        }
+    }
+
+    async fn replace_puzzle_difficulties_in_db(
+        &self,
+        difficulties: &[PuzzleDifficulty],
+    ) -> Result<(), StoreError> {
+        match self {
+            Store::Supabase(client) => client.replace_puzzle_difficulties_in_db(difficulties).await,
+            Store::D1(store) => store.replace_puzzle_difficulties_in_db(difficulties).await,
+        }
+    }
+
+    async fn fetch_result_scores(&self, date: &str) -> Result<Vec<ResultScore>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_result_scores(date).await,
+            Store::D1(store) => store.fetch_result_scores(date).await,
+        }
+    }
+
+    async fn fetch_best_result_scores(
+        &self,
+        username: &str,
+        limit: usize,
+    ) -> Result<Vec<ResultScore>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_best_result_scores(username, limit).await,
+            Store::D1(store) => store.fetch_best_result_scores(username, limit).await,
+        }
+    }
+
+    async fn fetch_relative_podium_data(
+        &self,
+        filter: &PodiumFilter,
+    ) -> Result<Vec<ResultScore>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_relative_podium_data(filter).await,
+            Store::D1(store) => store.fetch_relative_podium_data(filter).await,
+        }
+    }
+
+    async fn replace_result_scores_in_db(&self, scores: &[ResultScore]) -> Result<(), StoreError> {
+        match self {
+            Store::Supabase(client) => client.replace_result_scores_in_db(scores).await,
+            Store::D1(store) => store.replace_result_scores_in_db(scores).await,
+        }
+    }
+
+    async fn fetch_flagged_results(&self) -> Result<Vec<FlaggedResult>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_flagged_results().await,
+            Store::D1(store) => store.fetch_flagged_results().await,
+        }
+    }
+
+    async fn insert_flagged_results(&self, flags: &[FlaggedResult]) -> Result<(), StoreError> {
+        match self {
+            Store::Supabase(client) => client.insert_flagged_results(flags).await,
+            Store::D1(store) => store.insert_flagged_results(flags).await,
+        }
+    }
+
+    async fn update_flag_status(
+        &self,
+        date: &str,
+        username: &str,
+        status: ModerationStatus,
+    ) -> Result<(), StoreError> {
+        match self {
+            Store::Supabase(client) => client.update_flag_status(date, username, status).await,
+            Store::D1(store) => store.update_flag_status(date, username, status).await,
+        }
+    }
+
+    async fn delete_flagged_result(&self, date: &str, username: &str) -> Result<(), StoreError> {
+        match self {
+            Store::Supabase(client) => client.delete_flagged_result(date, username).await,
+            Store::D1(store) => store.delete_flagged_result(date, username).await,
+        }
+    }
+
+    async fn fetch_user_trueskill_from_db(
+        &self,
+        username: &str,
+    ) -> Result<Option<(f64, f64)>, StoreError> {
+        match self {
+            Store::Supabase(client) => client.fetch_user_trueskill_from_db(username).await,
+            Store::D1(store) => store.fetch_user_trueskill_from_db(username).await,
+        }
+    }
+}
@@ -1,6 +1,12 @@
 use askama::Template;
 
-use crate::models::{HeadToHeadData, LeaderboardEntry, ResultEntry};
+use crate::models::{
+    AuditAction, AuditEntry, ComparisonResponse, FavoritesResponse, FlaggedResult, HeadToHeadData,
+    HeadToHeadMatch, HeadToHeadMatrix, LeaderboardEntry, ModerationStatus, PredictionAccuracy,
+    PuzzleDifficulty, ResultEntry, ScoredResult, SeasonResults, SeasonStatus, TimePrediction,
+    UserStats, WeekdayStats,
+};
+use crate::rating::RatingSystemKind;
 
 mod filters {
     pub fn convert_time_to_mm_ss(seconds: &i32) -> ::askama::Result<String> {
@@ -16,12 +22,37 @@ mod filters {
     pub fn convert_decimal_to_percentage(decimal: &f64) -> ::askama::Result<String> {
         Ok(format!("{:.2}%", decimal * 100.0))
     }
+
+    /// Colors a table cell by a win rate or win probability, from red at 0 through yellow to green at 1.
+    pub fn win_rate_color(win_rate: &Option<f64>) -> ::askama::Result<String> {
+        Ok(match win_rate {
+            Some(win_rate) => format!("hsl({:.0}, 70%, 80%)", win_rate * 120.0),
+            None => String::from("transparent"),
+        })
+    }
+
+    pub fn format_score(score: &Option<f64>) -> ::askama::Result<String> {
+        Ok(match score {
+            Some(score) => format!("{score:+.2}"),
+            None => String::from("–"),
+        })
+    }
 }
 
 #[derive(Template)]
 #[template(path = "home.html")]
 pub struct LeaderboardTemplate {
     pub data: Vec<LeaderboardEntry>,
+    pub system: RatingSystemKind,
+    pub window_label: String,
+    /// The `(href, label, active)` of each preset window.
+    pub window_links: Vec<(String, String, bool)>,
+    /// The `(href, label, active)` of each rating system.
+    pub system_links: Vec<(String, &'static str, bool)>,
+    pub from: String,
+    pub to: String,
+    /// The likeliest winners of the next crossword, or `None` if they couldn't be predicted.
+    pub favorites: Option<FavoritesResponse>,
 }
 
 #[derive(Template)]
@@ -30,26 +61,62 @@ pub struct UserTemplate {
     pub username: String,
     pub scatter_plot_html: String,
     pub box_plot_html: String,
+    pub elo_plot_html: String,
     pub top_times: Vec<ResultEntry>,
+    pub stats: UserStats,
+    pub weekday_stats: Vec<WeekdayStats>,
+    pub weekday_plot_html: String,
+    pub best_relative_results: Vec<ScoredResult>,
+    pub predictions: Vec<TimePrediction>,
+    pub prediction_accuracy: Option<PredictionAccuracy>,
+    pub recent_prediction_accuracy: Option<PredictionAccuracy>,
+    pub prediction_plot_html: String,
 }
 
 #[derive(Template)]
 #[template(path = "podium.html")]
 pub struct PodiumTemplate {
-    pub data: Vec<ResultEntry>,
+    pub data: Vec<ScoredResult>,
+    pub title: String,
+    pub relative: bool,
+    pub weekday_options: Vec<(String, &'static str, bool)>,
+    pub month: usize,
+    pub year: String,
+    pub limit: usize,
 }
 
 #[derive(Template)]
 #[template(path = "history.html")]
 pub struct HistoryTemplate {
     pub date: String,
-    pub data: Vec<ResultEntry>,
+    pub data: Vec<ScoredResult>,
+    pub difficulty: Option<PuzzleDifficulty>,
 }
 
 #[derive(Template)]
 #[template(path = "recent.html")]
 pub struct RecentTemplate {
-    pub dates: Vec<String>,
+    pub puzzles: Vec<(String, Option<PuzzleDifficulty>)>,
+}
+
+#[derive(Template)]
+#[template(path = "puzzles.html")]
+pub struct PuzzlesTemplate {
+    pub hardest: Vec<PuzzleDifficulty>,
+    pub easiest: Vec<PuzzleDifficulty>,
+}
+
+#[derive(Template)]
+#[template(path = "seasons.html")]
+pub struct SeasonsTemplate {
+    /// Every season with its top three.
+    pub seasons: Vec<SeasonResults>,
+}
+
+#[derive(Template)]
+#[template(path = "season.html")]
+pub struct SeasonTemplate {
+    pub season: SeasonResults,
 }
 
 #[derive(Template, Default)]
@@ -60,6 +127,68 @@ pub struct HeadToHeadTemplate {
     pub box_plot_html: String,
     pub scatter_plot_html: String,
     pub win_probability: f64,
+    pub matches: Vec<HeadToHeadMatch>,
+    pub h2h_plot_html: String,
+}
+
+#[derive(Template, Default)]
+#[template(path = "compare.html")]
+pub struct CompareTemplate {
+    /// Every rated username, and whether it's being compared.
+    pub user_options: Vec<(String, bool)>,
+    pub comparison: Option<ComparisonResponse>,
+    pub scatter_plot_html: String,
+    pub box_plot_html: String,
+}
+
+#[derive(Template)]
+#[template(path = "h2h_matrix.html")]
+pub struct HeadToHeadMatrixTemplate {
+    pub matrix: HeadToHeadMatrix,
+    pub top: String,
+}
+
+#[derive(Template, Default)]
+#[template(path = "submit.html")]
+pub struct SubmitTemplate {
+    pub username: String,
+    pub text: String,
+    pub error: Option<String>,
+    pub submitted: Vec<ResultEntry>,
+}
+
+#[derive(Template)]
+#[template(path = "admin_login.html")]
+pub struct AdminLoginTemplate {
+    /// The admin page to return to after signing in.
+    pub next: String,
+    pub error: Option<String>,
+}
+
+#[derive(Template, Default)]
+#[template(path = "admin.html")]
+pub struct AdminTemplate {
+    pub audit_log: Vec<AuditEntry>,
+    pub message: Option<String>,
+    pub warning: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Template, Default)]
+#[template(path = "moderation.html")]
+pub struct ModerationTemplate {
+    pub flags: Vec<FlaggedResult>,
+    pub message: Option<String>,
+    pub warning: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "error.html")]
+pub struct ErrorTemplate {
+    pub status: u16,
+    pub title: &'static str,
+    pub message: String,
 }
 
 pub const CSS_STYLES: &str = "
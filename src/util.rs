@@ -1,13 +1,23 @@
+use chrono::{Datelike, Duration, Month, NaiveDate, Weekday};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use plotly::box_plot::BoxPoints;
-use plotly::color::Rgb;
-use plotly::common::{Line, Marker, Mode, Title};
+use plotly::color::{Rgb, Rgba};
+use plotly::common::{Fill, Line, LineShape, Marker, Mode, Title};
 use plotly::layout::{Axis, RangeSelector, RangeSlider, SelectorButton, SelectorStep, StepMode};
 use plotly::{BoxPlot, Layout, Plot, Scatter};
-use skillratings::trueskill::{expected_score, TrueSkillConfig, TrueSkillRating};
-use std::cmp::{max, min};
+use std::cmp::{max, min, Ordering};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error::Error;
 
-use crate::models::ResultEntry;
+use crate::models::{
+    ComparedPlayer, FlagReason, FlaggedResult, HeadToHeadData, HeadToHeadMatch, HeadToHeadMatrix,
+    HeadToHeadRecord, LeaderboardEntry, LeaderboardWindow, ModerationStatus, PodiumFilter,
+    PredictionAccuracy, PredictionOutcome, PuzzleDifficulty, RatingSnapshot, ResultEntry,
+    ScoredResult, Season, SeasonSchedule, SeasonStanding, SeasonStatus, Submission, TimePrediction,
+    UserData, UserStats, WeekdayStats,
+};
+use crate::parser::parse_clock_time;
+use crate::rating::{compute_leaderboard, Rating, RatingSystem, TrueSkill};
 
 use thiserror::Error;
 
@@ -21,6 +31,143 @@ pub enum PlottingError {
     MaxMovingAverageNotFound,
 }
 
+#[derive(Debug, Error)]
+pub enum SubmissionError {
+    #[error("Submission error: Date must be in YYYY-MM-DD format")]
+    InvalidDate,
+    #[error("Submission error: Date is in the future")]
+    FutureDate,
+    #[error("Submission error: Username must be between 1 and {MAX_USERNAME_LENGTH} characters")]
+    InvalidUsername,
+    #[error("Submission error: Time must be between 1 and {MAX_SOLVE_TIME} seconds")]
+    InvalidTime,
+    #[error("Submission error: {0} already has a result for {1}")]
+    AlreadySubmitted(String, String),
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum CorrectionError {
+    #[error("Correction error: Time must be a number of seconds or a clock time like 1:05")]
+    InvalidTime,
+    #[error("Correction error: Can't merge {0} into itself")]
+    SameUser(String),
+    #[error("Correction error: {0} and {1} both have a result for {2}")]
+    MergeConflict(String, String, String),
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum PodiumFilterError {
+    #[error("Podium filter error: Limit must be between 1 and {MAX_PODIUM_LIMIT}")]
+    InvalidLimit,
+    #[error("Podium filter error: Weekday must be a day of the week, e.g. \"sat\"")]
+    InvalidWeekday,
+    #[error("Podium filter error: Month must be between 1 and 12")]
+    InvalidMonth,
+    #[error("Podium filter error: Year must be a number, e.g. 2025")]
+    InvalidYear,
+    #[error("Podium filter error: Unknown parameter {0}")]
+    UnknownParameter(String),
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum LeaderboardWindowError {
+    #[error("Leaderboard window error: Unknown leaderboard {0}")]
+    UnknownWindow(String),
+    #[error("Leaderboard window error: Windows must be between 1 and {MAX_WINDOW_DAYS} days")]
+    InvalidDays,
+    #[error(
+        "Leaderboard window error: {0} isn't a valid date. Dates must be in YYYY-MM-DD format"
+    )]
+    InvalidDate(String),
+    #[error("Leaderboard window error: from and to can only be used with the all leaderboard")]
+    RangeWithWindow,
+    #[error("Leaderboard window error: from must not be after to")]
+    EmptyRange,
+    #[error("Leaderboard window error: A stored result has the invalid date {0}")]
+    InvalidResultDate(String),
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ComparisonError {
+    #[error("Comparison error: Pick at least two players to compare")]
+    TooFewUsers,
+    #[error("Comparison error: At most {MAX_COMPARED_USERS} players can be compared at once")]
+    TooManyUsers,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum SeasonError {
+    #[error("Season error: Unknown season length {0}. Use monthly, quarterly or custom")]
+    UnknownLength(String),
+    #[error("Season error: Season {0} has an invalid date. Dates must be in YYYY-MM-DD format")]
+    InvalidDate(String),
+    #[error("Season error: Season {0} ends before it starts")]
+    EmptySeason(String),
+    #[error("Season error: More than one season has the id {0}")]
+    DuplicateId(String),
+}
+
+/// The characters to percent-encode in a path segment: everything except RFC 3986's unreserved characters. This
+/// matches JavaScript's `encodeURIComponent` and Askama's `urlencode_strict` filter.
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// The days of the week, starting from Monday.
+pub const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// The fewest results a solver needs before their times count towards a puzzle's difficulty.
+const MIN_BASELINE_RESULTS: usize = 3;
+
+/// The fewest solvers a puzzle needs before its results are scored against the field.
+const MIN_FIELD_SIZE: usize = 3;
+
+/// The longest `last_N` leaderboard window, in days.
+const MAX_WINDOW_DAYS: i64 = 3650;
+
+/// The most players that can be compared at once.
+const MAX_COMPARED_USERS: usize = 8;
+
+/// The most results we show on a single podium.
+const MAX_PODIUM_LIMIT: usize = 100;
+
+/// The longest username we accept in a submission.
+const MAX_USERNAME_LENGTH: usize = 64;
+
+/// The slowest solve time (in seconds) we accept in a submission.
+const MAX_SOLVE_TIME: i32 = 3600;
+
+/// The fewest earlier results a solver needs before we predict their times.
+const MIN_PREDICTION_RESULTS: usize = 5;
+
+/// How many of a solver's most recent results their current form is fitted to.
+const TREND_RESULTS: usize = 30;
+
+/// How many results' worth of evidence a weekday's effect is shrunk towards zero by.
+const WEEKDAY_EFFECT_PRIOR: f64 = 3.0;
+
+/// The z-score bounding an 80% prediction interval.
+const PREDICTION_INTERVAL_Z: f64 = 1.2816;
+
+/// The fastest solve time (in seconds) we consider possible. Anything faster is flagged for review.
+const MIN_PLAUSIBLE_TIME: i32 = 8;
+
+/// The fewest other results a solver needs before a result can be flagged for beating their usual time.
+const MIN_OUTLIER_BASELINE_RESULTS: usize = 10;
+
+/// How many standard deviations faster than expected a result must be before it's flagged for review.
+const SUSPICIOUS_SCORE: f64 = 3.5;
+
 /// Computes the moving average for a given slice of `ResultEntry` values.
 ///
 /// # Arguments
@@ -67,6 +214,33 @@ fn compute_average_time(entries: &[ResultEntry]) -> i32 {
     entries.iter().map(|entry| entry.time).sum::<i32>() / entries.len() as i32
 }
 
+/// Builds the range selector buttons shown above every plot with a date axis.
+fn date_range_selector() -> RangeSelector {
+    RangeSelector::new().buttons(vec![
+        SelectorButton::new()
+            .count(1)
+            .label("1M")
+            .step(SelectorStep::Month)
+            .step_mode(StepMode::Backward),
+        SelectorButton::new()
+            .count(6)
+            .label("6M")
+            .step(SelectorStep::Month)
+            .step_mode(StepMode::Backward),
+        SelectorButton::new()
+            .count(1)
+            .label("YTD")
+            .step(SelectorStep::Year)
+            .step_mode(StepMode::ToDate),
+        SelectorButton::new()
+            .count(1)
+            .label("1Y")
+            .step(SelectorStep::Year)
+            .step_mode(StepMode::Backward),
+        SelectorButton::new().label("MAX").step(SelectorStep::All),
+    ])
+}
+
 /// Generates an HTML scatter plot for the given `ResultEntry` data.
 ///
 /// # Arguments
@@ -94,8 +268,15 @@ pub fn generate_scatter_plot_html(
         _ => false,
     };
 
+    let show_legend = all_user_entries.len() > 2;
+
     for user_entries in all_user_entries {
         user_entries.sort_by(|a, b| a.date.cmp(&b.date));
+        let username = user_entries
+            .first()
+            .ok_or(PlottingError::NotEnoughEntries)?
+            .username
+            .clone();
 
         let (dates, times) = compute_moving_averages(user_entries, 30, include_partial);
 
@@ -116,6 +297,7 @@ pub fn generate_scatter_plot_html(
         );
 
         let trace_times = Scatter::new(dates.clone(), times)
+            .name(&username)
             .mode(Mode::Lines)
             .opacity(0.7);
         plot.add_trace(trace_times);
@@ -127,29 +309,7 @@ pub fn generate_scatter_plot_html(
             .x_axis(
                 Axis::new()
                     .range_slider(RangeSlider::new().visible(true))
-                    .range_selector(RangeSelector::new().buttons(vec![
-                        SelectorButton::new()
-                            .count(1)
-                            .label("1M")
-                            .step(SelectorStep::Month)
-                            .step_mode(StepMode::Backward),
-                        SelectorButton::new()
-                            .count(6)
-                            .label("6M")
-                            .step(SelectorStep::Month)
-                            .step_mode(StepMode::Backward),
-                        SelectorButton::new()
-                            .count(1)
-                            .label("YTD")
-                            .step(SelectorStep::Year)
-                            .step_mode(StepMode::ToDate),
-                        SelectorButton::new()
-                            .count(1)
-                            .label("1Y")
-                            .step(SelectorStep::Year)
-                            .step_mode(StepMode::Backward),
-                        SelectorButton::new().label("MAX").step(SelectorStep::All),
-                    ]))
+                    .range_selector(date_range_selector())
                     .title(Title::from("Date")),
             )
             .y_axis(
@@ -161,13 +321,187 @@ pub fn generate_scatter_plot_html(
                         (max_moving_average as f64 * 1.1) as i32,
                     ]),
             )
-            .show_legend(false)
+            .show_legend(show_legend)
             .auto_size(true),
     );
 
     Ok(plot.to_inline_html(Some("scatter-plot")))
 }
 
+/// Generates an HTML line plot of a user's ELO over time.
+///
+/// # Arguments
+///
+/// * `history` - A slice of `RatingSnapshot` values for a single user, sorted by date.
+///
+/// # Returns
+///
+/// A `Result` containing the HTML string for the line plot, or a `PlottingError` if an error occurs.
+pub fn generate_elo_plot_html(history: &[RatingSnapshot]) -> Result<String, Box<dyn Error>> {
+    if history.is_empty() {
+        return Err(Box::new(PlottingError::NotEnoughEntries));
+    }
+
+    let dates: Vec<String> = history
+        .iter()
+        .map(|snapshot| snapshot.date.clone())
+        .collect();
+    let elos: Vec<f64> = history
+        .iter()
+        .map(|snapshot| snapshot.elo.round())
+        .collect();
+
+    let mut plot = Plot::new();
+    plot.add_trace(Scatter::new(dates, elos).mode(Mode::Lines));
+
+    plot.set_layout(
+        Layout::new()
+            .title(Title::new("ELO Over Time"))
+            .x_axis(
+                Axis::new()
+                    .range_slider(RangeSlider::new().visible(true))
+                    .range_selector(date_range_selector())
+                    .title(Title::from("Date")),
+            )
+            .y_axis(
+                Axis::new()
+                    .title(Title::from("ELO"))
+                    .grid_color(Rgb::new(243, 243, 243)),
+            )
+            .show_legend(false)
+            .auto_size(true),
+    );
+
+    Ok(plot.to_inline_html(Some("elo-plot")))
+}
+
+/// Generates an HTML step plot of user1's wins minus user2's wins over the crosswords they both played, so the
+/// line crosses zero whenever the lead changes hands.
+///
+/// # Arguments
+///
+/// * `matches` - The `HeadToHeadMatch` values for the two users, sorted by date.
+///
+/// # Returns
+///
+/// A `Result` containing the HTML string for the step plot, or a `PlottingError` if an error occurs.
+pub fn generate_h2h_plot_html(matches: &[HeadToHeadMatch]) -> Result<String, Box<dyn Error>> {
+    if matches.is_empty() {
+        return Err(Box::new(PlottingError::NotEnoughEntries));
+    }
+
+    let dates: Vec<String> = matches.iter().map(|m| m.date.clone()).collect();
+    let win_differences: Vec<i32> = matches.iter().map(|m| m.win_difference).collect();
+
+    let mut plot = Plot::new();
+    plot.add_trace(
+        Scatter::new(dates, win_differences)
+            .mode(Mode::Lines)
+            .line(Line::new().shape(LineShape::Hv)),
+    );
+
+    plot.set_layout(
+        Layout::new()
+            .title(Title::new("Wins Lead Over Time"))
+            .x_axis(
+                Axis::new()
+                    .range_slider(RangeSlider::new().visible(true))
+                    .range_selector(date_range_selector())
+                    .title(Title::from("Date")),
+            )
+            .y_axis(
+                Axis::new()
+                    .title(Title::from("Player 1 Wins − Player 2 Wins"))
+                    .grid_color(Rgb::new(243, 243, 243))
+                    .zero_line(true),
+            )
+            .show_legend(false)
+            .auto_size(true),
+    );
+
+    Ok(plot.to_inline_html(Some("h2h-plot")))
+}
+
+/// Generates an HTML plot of a user's actual times against the times predicted for them, with the prediction
+/// interval shaded.
+///
+/// # Arguments
+///
+/// * `outcomes` - The user's `PredictionOutcome` values, sorted by date.
+///
+/// # Returns
+///
+/// A `Result` containing the HTML string for the plot, or a `PlottingError` if an error occurs.
+pub fn generate_prediction_plot_html(
+    outcomes: &[PredictionOutcome],
+) -> Result<String, Box<dyn Error>> {
+    if outcomes.is_empty() {
+        return Err(Box::new(PlottingError::NotEnoughEntries));
+    }
+
+    let dates: Vec<String> = outcomes
+        .iter()
+        .map(|outcome| outcome.prediction.date.clone())
+        .collect();
+    let series = |value: fn(&PredictionOutcome) -> f64| -> Vec<f64> {
+        outcomes
+            .iter()
+            .map(|outcome| value(outcome).round())
+            .collect()
+    };
+
+    let mut plot = Plot::new();
+    plot.add_trace(
+        Scatter::new(dates.clone(), series(|outcome| outcome.prediction.low_time))
+            .mode(Mode::Lines)
+            .line(Line::new().width(0.0))
+            .name("Low"),
+    );
+    plot.add_trace(
+        Scatter::new(
+            dates.clone(),
+            series(|outcome| outcome.prediction.high_time),
+        )
+        .mode(Mode::Lines)
+        .line(Line::new().width(0.0))
+        .fill(Fill::ToNextY)
+        .fill_color(Rgba::new(0, 123, 255, 0.2))
+        .name("80% Range"),
+    );
+    plot.add_trace(
+        Scatter::new(
+            dates.clone(),
+            series(|outcome| outcome.prediction.predicted_time),
+        )
+        .mode(Mode::Lines)
+        .name("Predicted"),
+    );
+    plot.add_trace(
+        Scatter::new(dates, series(|outcome| f64::from(outcome.actual_time)))
+            .mode(Mode::Markers)
+            .name("Actual"),
+    );
+
+    plot.set_layout(
+        Layout::new()
+            .title(Title::new("Predicted vs. Actual Times"))
+            .x_axis(
+                Axis::new()
+                    .range_slider(RangeSlider::new().visible(true))
+                    .range_selector(date_range_selector())
+                    .title(Title::from("Date")),
+            )
+            .y_axis(
+                Axis::new()
+                    .title(Title::from("Time (seconds)"))
+                    .grid_color(Rgb::new(243, 243, 243)),
+            )
+            .auto_size(true),
+    );
+
+    Ok(plot.to_inline_html(Some("prediction-plot")))
+}
+
 /// Generates an HTML box plot for the given `ResultEntry` data.
 ///
 /// # Arguments
@@ -232,83 +566,1755 @@ pub fn generate_box_plot_html(
     Ok(plot.to_inline_html(Some("box-plot")))
 }
 
+/// Generates an HTML box plot of a user's times grouped by day of the week.
+///
+/// # Arguments
+///
+/// * `times_by_weekday` - The user's results for each day of the week, indexed by days from Monday.
+///
+/// # Returns
+///
+/// A `Result` containing the HTML string for the box plot, or a `PlottingError` if the user has no results.
+pub fn generate_weekday_box_plot_html(
+    times_by_weekday: &[Vec<ResultEntry>; 7],
+) -> Result<String, Box<dyn Error>> {
+    let max_average_time = times_by_weekday
+        .iter()
+        .filter(|entries| !entries.is_empty())
+        .map(|entries| compute_average_time(entries))
+        .max()
+        .ok_or(PlottingError::NotEnoughEntries)?;
+
+    let mut plot = Plot::new();
+
+    for (weekday, entries) in WEEKDAYS.into_iter().zip(times_by_weekday) {
+        let times: Vec<i32> = entries.iter().map(|entry| entry.time).collect();
+
+        let trace = BoxPlot::new(times)
+            .name(weekday_name(weekday))
+            .box_points(BoxPoints::All)
+            .jitter(0.6)
+            .whisker_width(0.2)
+            .marker(Marker::new().size(6))
+            .line(Line::new().width(2.0));
+        plot.add_trace(trace);
+    }
+
+    plot.set_layout(
+        Layout::new()
+            .title(Title::new("Times by Day of the Week"))
+            .y_axis(
+                Axis::new()
+                    .title(Title::from("Time (seconds)"))
+                    .show_grid(true)
+                    .zero_line(true)
+                    .dtick(10.0)
+                    .grid_color(Rgb::new(200, 200, 200))
+                    .grid_width(1)
+                    .zero_line_color(Rgb::new(200, 200, 200))
+                    .zero_line_width(2)
+                    .range(vec![0, 3 * max_average_time]),
+            )
+            .paper_background_color(Rgb::new(255, 255, 255))
+            .plot_background_color(Rgb::new(255, 255, 255))
+            .show_legend(false)
+            .auto_size(true),
+    );
+
+    Ok(plot.to_inline_html(Some("weekday-plot")))
+}
+
+/// Computes the probability that `user1` beats `user2` under the TrueSkill configuration the leaderboard is
+/// computed with.
+///
+/// # Arguments
+///
+/// * `user1` - The first user's `(mu, sigma)` rating.
+/// * `user2` - The second user's `(mu, sigma)` rating.
+///
+/// # Returns
+///
+/// The probability, between 0 and 1, that `user1` beats `user2`.
 pub fn compute_win_probability(user1: (f64, f64), user2: (f64, f64)) -> f64 {
-    expected_score(
-        &TrueSkillRating {
-            rating: user1.0,
-            uncertainty: user1.1,
+    TrueSkill.win_probability(
+        &Rating {
+            mu: user1.0,
+            sigma: user1.1,
         },
-        &TrueSkillRating {
-            rating: user2.0,
-            uncertainty: user2.1,
-        },
-        &TrueSkillConfig {
-            draw_probability: 0.05,
-            beta: 4.167,
-            default_dynamics: 0.0833,
+        &Rating {
+            mu: user2.0,
+            sigma: user2.1,
         },
     )
-    .0
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_compute_moving_averages_with_partial_averages() {
-        let entries = vec![
-            ResultEntry {
-                date: "2023-10-25".to_string(),
-                time: 100,
-                ..Default::default()
-            },
-            ResultEntry {
-                date: "2023-10-26".to_string(),
-                time: 120,
-                ..Default::default()
-            },
-            ResultEntry {
-                date: "2023-10-27".to_string(),
-                time: 110,
-                ..Default::default()
-            },
-        ];
-
-        let (dates, averages) = compute_moving_averages(&entries, 2, true);
+/// Computes the probability that each player beats each other player.
+///
+/// # Arguments
+///
+/// * `ratings` - Each player's `(mu, sigma)` rating.
+///
+/// # Returns
+///
+/// A matrix whose entry `[i][j]` is the probability that player `i` beats player `j`, or `None` when `i == j`.
+pub fn compute_win_probability_matrix(ratings: &[(f64, f64)]) -> Vec<Vec<Option<f64>>> {
+    ratings
+        .iter()
+        .enumerate()
+        .map(|(i, &rating)| {
+            ratings
+                .iter()
+                .enumerate()
+                .map(|(j, &other)| (i != j).then(|| compute_win_probability(rating, other)))
+                .collect()
+        })
+        .collect()
+}
 
-        assert_eq!(dates, vec!["2023-10-25", "2023-10-26", "2023-10-27"]);
-        assert_eq!(averages, vec![100, 110, 115]);
+/// Parses the players to compare from the values of every `users` query parameter, each of which can hold
+/// several comma-separated usernames. Blank and repeated usernames are ignored.
+///
+/// # Arguments
+///
+/// * `values` - The values of the `users` query parameters.
+///
+/// # Returns
+///
+/// A `Result` containing the usernames in the order given, which is empty if none were given, or a
+/// `ComparisonError` if there is only one or more than the maximum.
+pub fn parse_compared_users<V: AsRef<str>>(
+    values: impl IntoIterator<Item = V>,
+) -> Result<Vec<String>, ComparisonError> {
+    let mut usernames: Vec<String> = Vec::new();
+    for value in values {
+        for username in value.as_ref().split(',').map(str::trim) {
+            if !username.is_empty() && !usernames.iter().any(|other| other == username) {
+                usernames.push(username.to_string());
+            }
+        }
     }
 
-    #[test]
-    fn test_compute_moving_averages_without_partial_averages() {
-        let entries = vec![
-            ResultEntry {
-                date: "2023-10-25".to_string(),
-                time: 100,
-                ..Default::default()
-            },
-            ResultEntry {
-                date: "2023-10-26".to_string(),
-                time: 120,
-                ..Default::default()
-            },
-            ResultEntry {
-                date: "2023-10-27".to_string(),
-                time: 110,
-                ..Default::default()
-            },
-        ];
-
-        let (dates, averages) = compute_moving_averages(&entries, 2, false);
-
-        assert_eq!(dates, vec!["2023-10-26", "2023-10-27"]);
-        assert_eq!(averages, vec![110, 115]);
+    match usernames.len() {
+        1 => Err(ComparisonError::TooFewUsers),
+        len if len > MAX_COMPARED_USERS => Err(ComparisonError::TooManyUsers),
+        _ => Ok(usernames),
     }
+}
 
-    #[test]
-    fn test_compute_moving_averages_with_empty_input() {
+/// Summarizes a player's all-time stats for the compare page.
+///
+/// # Arguments
+///
+/// * `entry` - The player's all-time leaderboard entry.
+/// * `all_times` - Every result the player has recorded.
+///
+/// # Returns
+///
+/// A `ComparedPlayer`, or `None` if the player has no results.
+pub fn summarize_compared_player(
+    entry: LeaderboardEntry,
+    all_times: &[ResultEntry],
+) -> Option<ComparedPlayer> {
+    let times: Vec<i32> = all_times.iter().map(|result| result.time).collect();
+
+    Some(ComparedPlayer {
+        entry,
+        median_time: compute_median_time(&times)?,
+        best_time: *times.iter().min()?,
+    })
+}
+
+/// Keeps only the results from the `num_days` days ending at the most recent date in `results`.
+///
+/// # Arguments
+///
+/// * `results` - The results to filter.
+/// * `num_days` - The number of days to keep, including the most recent date.
+///
+/// # Returns
+///
+/// A `Result` containing the results in the window, or `LeaderboardWindowError::InvalidResultDate` if the most
+/// recent date can't be parsed.
+pub fn filter_to_recent_days(
+    results: &[ResultEntry],
+    num_days: i64,
+) -> Result<Vec<ResultEntry>, LeaderboardWindowError> {
+    let Some(most_recent_date) = results.iter().map(|entry| entry.date.as_str()).max() else {
+        return Ok(Vec::new());
+    };
+    let start_date = (NaiveDate::parse_from_str(most_recent_date, "%Y-%m-%d")
+        .map_err(|_| LeaderboardWindowError::InvalidResultDate(most_recent_date.to_string()))?
+        - Duration::days(num_days - 1))
+    .format("%Y-%m-%d")
+    .to_string();
+
+    Ok(results
+        .iter()
+        .filter(|entry| entry.date >= start_date)
+        .cloned()
+        .collect())
+}
+
+/// Parses a leaderboard window from the `/index/:db_name` route parameter and the `from` and `to` query
+/// parameters. Empty `from` and `to` values are ignored.
+///
+/// # Arguments
+///
+/// * `db_name` - `all`, `last_N` for any number of days N, `this_month` or `this_year`.
+/// * `from` - The first date of a custom range, in YYYY-MM-DD format. Only allowed with `all`.
+/// * `to` - The last date of a custom range, in YYYY-MM-DD format. Only allowed with `all`.
+///
+/// # Returns
+///
+/// A `Result` containing the `LeaderboardWindow`, or a `LeaderboardWindowError` describing what's invalid.
+pub fn parse_leaderboard_window(
+    db_name: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<LeaderboardWindow, LeaderboardWindowError> {
+    let parse_date = |date: Option<&str>| {
+        date.map(str::trim)
+            .filter(|date| !date.is_empty())
+            .map(|date| {
+                NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .map_err(|_| LeaderboardWindowError::InvalidDate(date.to_string()))
+            })
+            .transpose()
+    };
+    let (from, to) = (parse_date(from)?, parse_date(to)?);
+
+    let window = match db_name {
+        "all" => LeaderboardWindow::All,
+        "this_month" => LeaderboardWindow::ThisMonth,
+        "this_year" => LeaderboardWindow::ThisYear,
+        _ => {
+            let num_days = db_name
+                .strip_prefix("last_")
+                .ok_or_else(|| LeaderboardWindowError::UnknownWindow(db_name.to_string()))?
+                .parse()
+                .map_err(|_| LeaderboardWindowError::UnknownWindow(db_name.to_string()))?;
+            if !(1..=MAX_WINDOW_DAYS).contains(&num_days) {
+                return Err(LeaderboardWindowError::InvalidDays);
+            }
+            LeaderboardWindow::LastDays(num_days)
+        }
+    };
+
+    match (window, from, to) {
+        (_, None, None) => Ok(window),
+        (LeaderboardWindow::All, Some(from), Some(to)) if from > to => {
+            Err(LeaderboardWindowError::EmptyRange)
+        }
+        (LeaderboardWindow::All, from, to) => Ok(LeaderboardWindow::Range(from, to)),
+        _ => Err(LeaderboardWindowError::RangeWithWindow),
+    }
+}
+
+/// Keeps only the results inside a leaderboard window.
+///
+/// # Arguments
+///
+/// * `results` - The results to filter.
+/// * `window` - The window to keep.
+/// * `today` - Today's date, which determines the current month and year.
+///
+/// # Returns
+///
+/// A `Result` containing the results in the window, or `LeaderboardWindowError::InvalidResultDate` if a date
+/// can't be parsed.
+pub fn filter_to_window(
+    results: &[ResultEntry],
+    window: LeaderboardWindow,
+    today: NaiveDate,
+) -> Result<Vec<ResultEntry>, LeaderboardWindowError> {
+    let (from, to) = match window {
+        LeaderboardWindow::All => return Ok(results.to_vec()),
+        LeaderboardWindow::LastDays(num_days) => return filter_to_recent_days(results, num_days),
+        LeaderboardWindow::ThisMonth => (today.with_day(1), None),
+        LeaderboardWindow::ThisYear => (today.with_ordinal(1), None),
+        LeaderboardWindow::Range(from, to) => (from, to),
+    };
+    let from = from.map(|date| date.format("%Y-%m-%d").to_string());
+    let to = to.map(|date| date.format("%Y-%m-%d").to_string());
+
+    Ok(results
+        .iter()
+        .filter(|entry| {
+            from.as_ref().is_none_or(|from| entry.date >= *from)
+                && to.as_ref().is_none_or(|to| entry.date <= *to)
+        })
+        .cloned()
+        .collect())
+}
+
+/// Describes a leaderboard window as a page subtitle, e.g. "Last 30 Days".
+pub fn describe_leaderboard_window(window: LeaderboardWindow) -> String {
+    match window {
+        LeaderboardWindow::All => String::from("All Time"),
+        LeaderboardWindow::LastDays(1) => String::from("Last Day"),
+        LeaderboardWindow::LastDays(num_days) => format!("Last {num_days} Days"),
+        LeaderboardWindow::ThisMonth => String::from("This Month"),
+        LeaderboardWindow::ThisYear => String::from("This Year"),
+        LeaderboardWindow::Range(Some(from), Some(to)) => format!("{from} to {to}"),
+        LeaderboardWindow::Range(Some(from), None) => format!("Since {from}"),
+        LeaderboardWindow::Range(None, Some(to)) => format!("Until {to}"),
+        LeaderboardWindow::Range(None, None) => String::from("All Time"),
+    }
+}
+
+/// Links to the leaderboard for a window under a rating system.
+///
+/// # Arguments
+///
+/// * `window` - The leaderboard window.
+/// * `system_id` - The rating system's `id()`.
+///
+/// # Returns
+///
+/// The path and query string of the leaderboard page.
+pub fn leaderboard_link(window: LeaderboardWindow, system_id: &str) -> String {
+    match window {
+        LeaderboardWindow::All => format!("/index/all?system={system_id}"),
+        LeaderboardWindow::LastDays(num_days) => {
+            format!("/index/last_{num_days}?system={system_id}")
+        }
+        LeaderboardWindow::ThisMonth => format!("/index/this_month?system={system_id}"),
+        LeaderboardWindow::ThisYear => format!("/index/this_year?system={system_id}"),
+        LeaderboardWindow::Range(from, to) => {
+            let format_date = |date: Option<NaiveDate>| {
+                date.map(|date| date.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default()
+            };
+            format!(
+                "/index/all?from={}&to={}&system={system_id}",
+                format_date(from),
+                format_date(to)
+            )
+        }
+    }
+}
+
+/// Parses a season's start and end dates.
+fn parse_season_dates(season: &Season) -> Result<(NaiveDate, NaiveDate), SeasonError> {
+    let parse_date = |date: &str| {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| SeasonError::InvalidDate(season.id.clone()))
+    };
+    let (start_date, end_date) = (
+        parse_date(&season.start_date)?,
+        parse_date(&season.end_date)?,
+    );
+
+    if end_date < start_date {
+        return Err(SeasonError::EmptySeason(season.id.clone()));
+    }
+
+    Ok((start_date, end_date))
+}
+
+/// Lists every season that has started, plus any upcoming custom seasons, most recent first.
+///
+/// Monthly seasons have ids like `2026-10` and quarterly seasons have ids like `2026-q4`. They run from the
+/// season containing `first_date` to the one containing `today`.
+///
+/// # Arguments
+///
+/// * `schedule` - How the calendar is divided into seasons.
+/// * `first_date` - The date of the first crossword, which starts the first monthly or quarterly season.
+/// * `today` - Today's date, which ends the last monthly or quarterly season.
+///
+/// # Returns
+///
+/// A `Result` containing the seasons sorted by start date in descending order, or a `SeasonError` if a custom
+/// season is invalid.
+pub fn list_seasons(
+    schedule: &SeasonSchedule,
+    first_date: NaiveDate,
+    today: NaiveDate,
+) -> Result<Vec<Season>, SeasonError> {
+    let num_months = match schedule {
+        SeasonSchedule::Monthly => 1,
+        SeasonSchedule::Quarterly => 3,
+        SeasonSchedule::Custom(seasons) => {
+            for (i, season) in seasons.iter().enumerate() {
+                parse_season_dates(season)?;
+                if seasons[..i].iter().any(|other| other.id == season.id) {
+                    return Err(SeasonError::DuplicateId(season.id.clone()));
+                }
+            }
+
+            let mut seasons = seasons.clone();
+            seasons.sort_by(|a, b| b.start_date.cmp(&a.start_date));
+            return Ok(seasons);
+        }
+    };
+
+    // Months are counted from year 0 so that seasons can be stepped through without overflowing a date.
+    let month_index = |date: NaiveDate| date.year() * 12 + date.month0() as i32;
+    let first_day_of = |index: i32| {
+        NaiveDate::from_ymd_opt(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, 1)
+    };
+
+    let mut seasons = Vec::new();
+    let mut index = month_index(first_date) - month_index(first_date).rem_euclid(num_months);
+    while index <= month_index(today) {
+        let (Some(start_date), Some(end_date)) = (
+            first_day_of(index),
+            first_day_of(index + num_months).and_then(|date| date.pred_opt()),
+        ) else {
+            break;
+        };
+
+        let (id, name) = if num_months == 1 {
+            (
+                start_date.format("%Y-%m").to_string(),
+                start_date.format("%B %Y").to_string(),
+            )
+        } else {
+            let quarter = start_date.month0() / 3 + 1;
+            (
+                format!("{}-q{quarter}", start_date.year()),
+                format!("Q{quarter} {}", start_date.year()),
+            )
+        };
+
+        seasons.push(Season {
+            id,
+            name,
+            start_date: start_date.format("%Y-%m-%d").to_string(),
+            end_date: end_date.format("%Y-%m-%d").to_string(),
+        });
+        index += num_months;
+    }
+    seasons.reverse();
+
+    Ok(seasons)
+}
+
+/// Determines whether a season is upcoming, current or completed. A season is current through its last day.
+pub fn season_status(season: &Season, today: NaiveDate) -> SeasonStatus {
+    let today = today.format("%Y-%m-%d").to_string();
+
+    if season.start_date > today {
+        SeasonStatus::Upcoming
+    } else if season.end_date < today {
+        SeasonStatus::Completed
+    } else {
+        SeasonStatus::Current
+    }
+}
+
+/// Ranks the players in a season with TrueSkill, starting everyone from a fresh rating.
+///
+/// # Arguments
+///
+/// * `season` - The season to rank.
+/// * `results` - Results from any date. Those outside the season are ignored.
+///
+/// # Returns
+///
+/// A vector of `SeasonStanding` structs, sorted by rank.
+pub fn compute_season_standings(season: &Season, results: &[ResultEntry]) -> Vec<SeasonStanding> {
+    let season_results: Vec<ResultEntry> = results
+        .iter()
+        .filter(|entry| entry.date >= season.start_date && entry.date <= season.end_date)
+        .cloned()
+        .collect();
+
+    compute_leaderboard(&season_results, &TrueSkill)
+        .into_iter()
+        .zip(1..)
+        .map(|(entry, rank)| SeasonStanding {
+            season_id: season.id.clone(),
+            rank,
+            username: entry.username,
+            mu: entry.mu,
+            sigma: entry.sigma,
+            average_time: entry.average_time,
+            num_wins: entry.num_wins,
+            num_played: entry.num_played,
+            elo: entry.elo,
+        })
+        .collect()
+}
+
+/// Validates a manual submission and converts it into an unranked `ResultEntry`.
+///
+/// # Arguments
+///
+/// * `submission` - The submission to validate.
+/// * `today` - Today's date. The next day's puzzle is published the evening before, so dates up to one day
+///   after `today` are accepted.
+///
+/// # Returns
+///
+/// A `Result` containing the `ResultEntry` with a rank of 0, or a `SubmissionError` describing the first invalid field.
+pub fn validate_submission(
+    submission: Submission,
+    today: NaiveDate,
+) -> Result<ResultEntry, SubmissionError> {
+    let date = NaiveDate::parse_from_str(submission.date.trim(), "%Y-%m-%d")
+        .map_err(|_| SubmissionError::InvalidDate)?;
+    if date > today + Duration::days(1) {
+        return Err(SubmissionError::FutureDate);
+    }
+
+    let username = submission.username.trim();
+    if username.is_empty() || username.chars().count() > MAX_USERNAME_LENGTH {
+        return Err(SubmissionError::InvalidUsername);
+    }
+
+    if !(1..=MAX_SOLVE_TIME).contains(&submission.time) {
+        return Err(SubmissionError::InvalidTime);
+    }
+
+    Ok(ResultEntry {
+        date: date.format("%Y-%m-%d").to_string(),
+        time: submission.time,
+        username: username.to_string(),
+        rank: 0,
+    })
+}
+
+/// Sorts the results for a single date by time and assigns each one its rank.
+///
+/// Tied times share the best rank, and the next slower time skips ahead by the number of tied entries
+/// (e.g. 1, 1, 3), matching how ranks were originally assigned when fetching the NYT leaderboard.
+///
+/// # Arguments
+///
+/// * `entries` - A mutable slice of `ResultEntry` values, all for the same date.
+pub fn compute_ranks(entries: &mut [ResultEntry]) {
+    entries.sort_by_key(|entry| entry.time);
+
+    let mut rank = 0;
+    let mut previous_time = None;
+    for (i, entry) in entries.iter_mut().enumerate() {
+        if previous_time != Some(entry.time) {
+            rank = i as i32 + 1;
+            previous_time = Some(entry.time);
+        }
+        entry.rank = rank;
+    }
+}
+
+/// Merges new results for a date into the existing ones and re-ranks them.
+///
+/// # Arguments
+///
+/// * `date` - A string representing the date in "YYYY-MM-DD" format.
+/// * `existing_entries` - The results already recorded for `date`.
+/// * `new_entries` - The results to add. Their dates and ranks are overwritten.
+///
+/// # Returns
+///
+/// A `Result` containing the new entries with their ranks and the existing entries whose rank changed, or a
+/// `SubmissionError` if a user already has a result for `date` or appears more than once in `new_entries`.
+pub fn merge_new_results(
+    date: &str,
+    existing_entries: &[ResultEntry],
+    new_entries: Vec<ResultEntry>,
+) -> Result<(Vec<ResultEntry>, Vec<ResultEntry>), SubmissionError> {
+    if let Some((_, duplicate)) = new_entries.iter().enumerate().find(|(i, new_entry)| {
+        existing_entries
+            .iter()
+            .chain(&new_entries[..*i])
+            .any(|entry| entry.username == new_entry.username)
+    }) {
+        return Err(SubmissionError::AlreadySubmitted(
+            duplicate.username.clone(),
+            date.to_string(),
+        ));
+    }
+
+    let mut all_entries: Vec<ResultEntry> = existing_entries
+        .iter()
+        .cloned()
+        .chain(new_entries.into_iter().map(|entry| ResultEntry {
+            date: date.to_string(),
+            ..entry
+        }))
+        .collect();
+    compute_ranks(&mut all_entries);
+
+    let (inserted_entries, reranked_entries): (Vec<ResultEntry>, Vec<ResultEntry>) =
+        all_entries.into_iter().partition(|entry| {
+            !existing_entries
+                .iter()
+                .any(|existing| existing.username == entry.username)
+        });
+
+    let reranked_entries = reranked_entries
+        .into_iter()
+        .filter(|entry| {
+            existing_entries
+                .iter()
+                .any(|existing| existing.username == entry.username && existing.rank != entry.rank)
+        })
+        .collect();
+
+    Ok((inserted_entries, reranked_entries))
+}
+
+/// Re-ranks the results for a single date after one of them was changed or removed.
+///
+/// # Arguments
+///
+/// * `entries` - The results now recorded for the date, with their old ranks.
+///
+/// # Returns
+///
+/// The entries whose rank changed, with their new ranks.
+pub fn rerank_results(mut entries: Vec<ResultEntry>) -> Vec<ResultEntry> {
+    let old_ranks: HashMap<String, i32> = entries
+        .iter()
+        .map(|entry| (entry.username.clone(), entry.rank))
+        .collect();
+    compute_ranks(&mut entries);
+
+    entries
+        .into_iter()
+        .filter(|entry| old_ranks.get(&entry.username) != Some(&entry.rank))
+        .collect()
+}
+
+/// Parses a corrected solve time, given either in seconds ("65") or as a clock time ("1:05").
+///
+/// # Returns
+///
+/// The time in seconds, or `CorrectionError::InvalidTime` if it's neither.
+pub fn parse_corrected_time(time: &str) -> Result<i32, CorrectionError> {
+    let time = time.trim();
+
+    time.parse::<i32>()
+        .ok()
+        .or_else(|| parse_clock_time(time))
+        .ok_or(CorrectionError::InvalidTime)
+}
+
+/// Checks that one user's results can be merged into another's.
+///
+/// # Arguments
+///
+/// * `from` - The username being merged away.
+/// * `to` - The username to merge into.
+/// * `from_results` - Every result recorded for `from`.
+/// * `to_results` - Every result recorded for `to`.
+///
+/// # Returns
+///
+/// A `CorrectionError` if the users are the same or both have a result for the same date, since a user can only
+/// have one result per date.
+pub fn validate_merge(
+    from: &str,
+    to: &str,
+    from_results: &[ResultEntry],
+    to_results: &[ResultEntry],
+) -> Result<(), CorrectionError> {
+    if from == to {
+        return Err(CorrectionError::SameUser(from.to_string()));
+    }
+
+    match from_results
+        .iter()
+        .find(|entry| to_results.iter().any(|other| other.date == entry.date))
+    {
+        Some(entry) => Err(CorrectionError::MergeConflict(
+            from.to_string(),
+            to.to_string(),
+            entry.date.clone(),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Builds a user's `UserData` from all of their results.
+///
+/// # Arguments
+///
+/// * `all_times` - Every result recorded for the user, sorted by time.
+///
+/// # Returns
+///
+/// A `UserData` struct containing every result and the results from days other than Saturday.
+pub fn build_user_data(all_times: Vec<ResultEntry>) -> UserData {
+    let mut times_by_weekday: [Vec<ResultEntry>; 7] = Default::default();
+    for entry in &all_times {
+        if let Ok(date) = NaiveDate::parse_from_str(entry.date.as_str(), "%Y-%m-%d") {
+            times_by_weekday[date.weekday().num_days_from_monday() as usize].push(entry.clone());
+        }
+    }
+
+    let times_excluding_saturday: Vec<ResultEntry> = all_times
+        .iter()
+        .filter(|entry| {
+            NaiveDate::parse_from_str(entry.date.as_str(), "%Y-%m-%d")
+                .map(|date| date.weekday() != Weekday::Sat)
+                .unwrap_or(false)
+        })
+        .cloned()
+        .collect();
+
+    UserData {
+        all_times,
+        times_excluding_saturday,
+        times_by_weekday,
+    }
+}
+
+/// Computes the median of a list of values.
+///
+/// # Returns
+///
+/// The median, or `None` if `values` is empty.
+fn compute_median(mut values: Vec<f64>) -> Option<f64> {
+    values.sort_by(f64::total_cmp);
+
+    let middle = values.len() / 2;
+    match values.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((values[middle - 1] + values[middle]) / 2.0),
+        _ => Some(values[middle]),
+    }
+}
+
+/// Computes the median of a list of solve times.
+fn compute_median_time(times: &[i32]) -> Option<f64> {
+    compute_median(times.iter().copied().map(f64::from).collect())
+}
+
+/// Describes a difficulty score in words.
+fn describe_difficulty(difficulty: f64) -> &'static str {
+    match difficulty {
+        d if d < 0.8 => "Very Easy",
+        d if d < 0.95 => "Easy",
+        d if d <= 1.05 => "Average",
+        d if d <= 1.25 => "Hard",
+        _ => "Very Hard",
+    }
+}
+
+/// Computes the difficulty of every puzzle.
+///
+/// Each solver's baseline is their median time over every puzzle. A puzzle's difficulty is the median, over
+/// everyone who solved it, of their time divided by their baseline, so it isn't skewed by which players happened
+/// to show up that day. Solvers with fewer than `MIN_BASELINE_RESULTS` results don't have a reliable baseline
+/// and are left out.
+///
+/// # Arguments
+///
+/// * `results` - Every stored result, in any order.
+///
+/// # Returns
+///
+/// A `PuzzleDifficulty` for every date with at least one solver who has a baseline, sorted by date.
+pub fn compute_puzzle_difficulties(results: &[ResultEntry]) -> Vec<PuzzleDifficulty> {
+    let mut times_by_user: HashMap<&str, Vec<i32>> = HashMap::new();
+    let mut results_by_date: BTreeMap<&str, Vec<&ResultEntry>> = BTreeMap::new();
+    for entry in results {
+        times_by_user
+            .entry(entry.username.as_str())
+            .or_default()
+            .push(entry.time);
+        results_by_date
+            .entry(entry.date.as_str())
+            .or_default()
+            .push(entry);
+    }
+
+    let baselines: HashMap<&str, f64> = times_by_user
+        .into_iter()
+        .filter(|(_, times)| times.len() >= MIN_BASELINE_RESULTS)
+        .filter_map(|(username, times)| Some((username, compute_median_time(&times)?)))
+        .collect();
+
+    results_by_date
+        .into_iter()
+        .filter_map(|(date, entries)| {
+            let difficulty = compute_median(
+                entries
+                    .iter()
+                    .filter_map(|entry| {
+                        let baseline = baselines.get(entry.username.as_str())?;
+                        Some(f64::from(entry.time) / baseline)
+                    })
+                    .collect(),
+            )?;
+            let times: Vec<i32> = entries.iter().map(|entry| entry.time).collect();
+
+            Some(PuzzleDifficulty {
+                date: date.to_string(),
+                difficulty,
+                label: describe_difficulty(difficulty).to_string(),
+                median_time: compute_median_time(&times)?,
+                num_solvers: entries.len(),
+            })
+        })
+        .collect()
+}
+
+/// Computes the mean and standard deviation of the log of each solve time.
+///
+/// # Returns
+///
+/// The `(mean, standard_deviation)`, or `None` if `times` is empty or every time is the same.
+fn log_time_distribution(times: &[i32]) -> Option<(f64, f64)> {
+    if times.is_empty() {
+        return None;
+    }
+
+    let log_times: Vec<f64> = times.iter().map(|time| log_time(*time)).collect();
+    let mean = log_times.iter().sum::<f64>() / log_times.len() as f64;
+    let variance = log_times
+        .iter()
+        .map(|log_time| (log_time - mean).powi(2))
+        .sum::<f64>()
+        / log_times.len() as f64;
+
+    let standard_deviation = variance.sqrt();
+    (standard_deviation > 0.0).then_some((mean, standard_deviation))
+}
+
+/// The log of a solve time, treating anything under a second as one second.
+fn log_time(time: i32) -> f64 {
+    f64::from(max(time, 1)).ln()
+}
+
+/// Scores every result against that day's field and against the solver's own history.
+///
+/// Solve times are skewed, with a long tail of slow days, so the scores compare log times. A field score of 1.0
+/// means the solve was one standard deviation faster than that day's typical solve. Puzzles with fewer than
+/// `MIN_FIELD_SIZE` solvers and solvers with fewer than `MIN_BASELINE_RESULTS` results aren't scored.
+///
+/// # Arguments
+///
+/// * `results` - Every stored result, in any order.
+///
+/// # Returns
+///
+/// A `ScoredResult` for each result, in the same order as `results`.
+pub fn score_results(results: &[ResultEntry]) -> Vec<ScoredResult> {
+    let mut times_by_date: HashMap<&str, Vec<i32>> = HashMap::new();
+    let mut times_by_user: HashMap<&str, Vec<i32>> = HashMap::new();
+    for entry in results {
+        times_by_date
+            .entry(entry.date.as_str())
+            .or_default()
+            .push(entry.time);
+        times_by_user
+            .entry(entry.username.as_str())
+            .or_default()
+            .push(entry.time);
+    }
+
+    let field: HashMap<&str, (f64, f64)> = times_by_date
+        .into_iter()
+        .filter(|(_, times)| times.len() >= MIN_FIELD_SIZE)
+        .filter_map(|(date, times)| Some((date, log_time_distribution(&times)?)))
+        .collect();
+    let personal: HashMap<&str, (f64, f64)> = times_by_user
+        .into_iter()
+        .filter(|(_, times)| times.len() >= MIN_BASELINE_RESULTS)
+        .filter_map(|(username, times)| Some((username, log_time_distribution(&times)?)))
+        .collect();
+
+    let score = |distribution: Option<&(f64, f64)>, time: i32| {
+        distribution.map(|(mean, standard_deviation)| (mean - log_time(time)) / standard_deviation)
+    };
+
+    results
+        .iter()
+        .map(|entry| ScoredResult {
+            result: entry.clone(),
+            field_score: score(field.get(entry.date.as_str()), entry.time),
+            personal_score: score(personal.get(entry.username.as_str()), entry.time),
+        })
+        .collect()
+}
+
+/// Scores a log time against a group of log times it belongs to, leaving it out of the group first.
+///
+/// # Arguments
+///
+/// * `(count, sum, sum_of_squares)` - Totals of the group's log times, including `log_time`.
+/// * `log_time` - The log time to score.
+/// * `min_others` - The fewest other log times the group needs for the score to mean anything.
+///
+/// # Returns
+///
+/// How many standard deviations faster than the rest of the group `log_time` is, or `None` if the rest of the
+/// group is too small or all the same.
+fn leave_one_out_score(
+    (count, sum, sum_of_squares): (usize, f64, f64),
+    log_time: f64,
+    min_others: usize,
+) -> Option<f64> {
+    let others = count
+        .checked_sub(1)
+        .filter(|others| *others >= min_others)? as f64;
+    let mean = (sum - log_time) / others;
+    let variance = (sum_of_squares - log_time.powi(2)) / others - mean.powi(2);
+
+    (variance > f64::EPSILON).then(|| (mean - log_time) / variance.sqrt())
+}
+
+/// Finds results that look wrong: impossibly fast times, and times far faster than either the user's usual time or
+/// the rest of that day's field.
+///
+/// Each result is compared to every other result, leaving itself out, so a single bogus time can't hide by
+/// dragging its own baseline down.
+///
+/// # Arguments
+///
+/// * `results` - Every stored result, in any order.
+///
+/// # Returns
+///
+/// A pending `FlaggedResult` for each suspicious result, with the first reason that applies, in the same order as
+/// `results`.
+pub fn detect_suspicious_results(results: &[ResultEntry]) -> Vec<FlaggedResult> {
+    let mut totals_by_date: HashMap<&str, (usize, f64, f64)> = HashMap::new();
+    let mut totals_by_user: HashMap<&str, (usize, f64, f64)> = HashMap::new();
+    for entry in results {
+        let log_time = log_time(entry.time);
+        for totals in [
+            totals_by_date.entry(entry.date.as_str()).or_default(),
+            totals_by_user.entry(entry.username.as_str()).or_default(),
+        ] {
+            totals.0 += 1;
+            totals.1 += log_time;
+            totals.2 += log_time.powi(2);
+        }
+    }
+
+    results
+        .iter()
+        .filter_map(|entry| {
+            let log_time = log_time(entry.time);
+            let personal_score = leave_one_out_score(
+                totals_by_user[entry.username.as_str()],
+                log_time,
+                MIN_OUTLIER_BASELINE_RESULTS,
+            );
+            let field_score = leave_one_out_score(
+                totals_by_date[entry.date.as_str()],
+                log_time,
+                MIN_FIELD_SIZE,
+            );
+
+            let (reason, detail) = if entry.time < MIN_PLAUSIBLE_TIME {
+                (
+                    FlagReason::ImpossibleTime,
+                    format!("Solved in under {MIN_PLAUSIBLE_TIME} seconds"),
+                )
+            } else if let Some(score) = personal_score.filter(|score| *score >= SUSPICIOUS_SCORE) {
+                (
+                    FlagReason::PersonalOutlier,
+                    format!("{score:.1} standard deviations faster than their usual time"),
+                )
+            } else if let Some(score) = field_score.filter(|score| *score >= SUSPICIOUS_SCORE) {
+                (
+                    FlagReason::FieldOutlier,
+                    format!("{score:.1} standard deviations faster than the rest of the field"),
+                )
+            } else {
+                return None;
+            };
+
+            Some(FlaggedResult {
+                date: entry.date.clone(),
+                username: entry.username.clone(),
+                time: entry.time,
+                reason,
+                detail,
+                status: ModerationStatus::Pending,
+            })
+        })
+        .collect()
+}
+
+/// Drops every result with a flag that hasn't been approved.
+///
+/// # Arguments
+///
+/// * `results` - The results to filter.
+/// * `flags` - Every flagged result.
+///
+/// # Returns
+///
+/// The results that count towards ratings, in the same order as `results`.
+pub fn exclude_flagged_results(
+    results: Vec<ResultEntry>,
+    flags: &[FlaggedResult],
+) -> Vec<ResultEntry> {
+    let excluded: HashSet<(&str, &str)> = flags
+        .iter()
+        .filter(|flag| flag.status != ModerationStatus::Approved)
+        .map(|flag| (flag.date.as_str(), flag.username.as_str()))
+        .collect();
+
+    results
+        .into_iter()
+        .filter(|entry| !excluded.contains(&(entry.date.as_str(), entry.username.as_str())))
+        .collect()
+}
+
+/// Computes a user's statistics for each day of the week they've played.
+///
+/// # Arguments
+///
+/// * `data` - The user's `UserData`.
+///
+/// # Returns
+///
+/// A `WeekdayStats` for each day of the week with at least one result, starting from Monday.
+pub fn compute_weekday_stats(data: &UserData) -> Vec<WeekdayStats> {
+    WEEKDAYS
+        .into_iter()
+        .zip(&data.times_by_weekday)
+        .filter_map(|(weekday, entries)| {
+            let times: Vec<i32> = entries.iter().map(|entry| entry.time).collect();
+            let count = times.len();
+
+            Some(WeekdayStats {
+                weekday: weekday_name(weekday),
+                count,
+                average_time: f64::from(times.iter().sum::<i32>()) / count as f64,
+                best_time: *times.iter().min()?,
+                median_time: compute_median_time(&times)?,
+                win_rate: entries.iter().filter(|entry| entry.rank == 1).count() as f64
+                    / count as f64,
+            })
+        })
+        .collect()
+}
+
+/// Pairs each result's date with the log of its time, oldest first. Results with malformed dates are skipped.
+fn dated_log_times(entries: &[ResultEntry]) -> Vec<(NaiveDate, i32, f64)> {
+    let mut dated: Vec<(NaiveDate, i32, f64)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let date = NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d").ok()?;
+            Some((date, entry.time, log_time(entry.time)))
+        })
+        .collect();
+    dated.sort_by_key(|(date, ..)| *date);
+    dated
+}
+
+/// Predicts the log of a solver's time on `date` from their earlier results.
+///
+/// The prediction is the sum of the solver's current form and how much slower or faster they are on that day of
+/// the week. Each weekday's effect is the average amount by which its log times differ from the solver's overall
+/// average, shrunk towards zero until the weekday has a few results. The current form is a line fitted to the last
+/// `TREND_RESULTS` log times once weekday effects are taken out, so a solver who's been getting faster is predicted
+/// to keep getting faster.
+///
+/// # Arguments
+///
+/// * `history` - The solver's earlier results as `(date, time, log time)`, oldest first.
+/// * `date` - The date to predict a time for.
+///
+/// # Returns
+///
+/// The predicted log time and the standard deviation of the line's residuals, or `None` if there are fewer than
+/// `MIN_PREDICTION_RESULTS` results.
+fn predict_log_time(history: &[(NaiveDate, i32, f64)], date: NaiveDate) -> Option<(f64, f64)> {
+    if history.len() < MIN_PREDICTION_RESULTS {
+        return None;
+    }
+
+    let mean = history.iter().map(|(.., log_time)| log_time).sum::<f64>() / history.len() as f64;
+    let mut sums = [0.0; 7];
+    let mut counts = [0.0; 7];
+    for (day, _, log_time) in history {
+        let weekday = day.weekday().num_days_from_monday() as usize;
+        sums[weekday] += log_time - mean;
+        counts[weekday] += 1.0;
+    }
+    let effect = |day: NaiveDate| {
+        let weekday = day.weekday().num_days_from_monday() as usize;
+        sums[weekday] / (counts[weekday] + WEEKDAY_EFFECT_PRIOR)
+    };
+
+    let recent: Vec<(f64, f64)> = history[history.len().saturating_sub(TREND_RESULTS)..]
+        .iter()
+        .map(|(day, _, log_time)| ((*day - date).num_days() as f64, log_time - effect(*day)))
+        .collect();
+    let n = recent.len() as f64;
+    let mean_x = recent.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = recent.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let sxx: f64 = recent.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = recent
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let slope = if sxx > 0.0 { sxy / sxx } else { 0.0 };
+    let form = mean_y - slope * mean_x;
+
+    let residuals: f64 = recent
+        .iter()
+        .map(|(x, y)| (y - (mean_y + slope * (x - mean_x))).powi(2))
+        .sum();
+    let standard_deviation = (residuals / (n - 2.0)).sqrt();
+
+    Some((form + effect(date), standard_deviation))
+}
+
+/// Builds a `TimePrediction` from a predicted log time and its standard deviation.
+fn time_prediction(date: NaiveDate, (log_time, standard_deviation): (f64, f64)) -> TimePrediction {
+    let margin = PREDICTION_INTERVAL_Z * standard_deviation;
+    TimePrediction {
+        date: date.format("%Y-%m-%d").to_string(),
+        weekday: weekday_name(date.weekday()),
+        predicted_time: log_time.exp(),
+        low_time: (log_time - margin).exp(),
+        high_time: (log_time + margin).exp(),
+    }
+}
+
+/// Predicts a solver's time for each of the seven days starting at `first_date`, so every day of the week is
+/// covered.
+///
+/// # Arguments
+///
+/// * `entries` - Every result recorded for the solver, in any order.
+/// * `first_date` - The first date to predict, usually the day after the most recent crossword.
+///
+/// # Returns
+///
+/// A `TimePrediction` for each day, in date order, or an empty vector if the solver doesn't have enough results.
+pub fn predict_solve_times(entries: &[ResultEntry], first_date: NaiveDate) -> Vec<TimePrediction> {
+    let history = dated_log_times(entries);
+
+    first_date
+        .iter_days()
+        .take(7)
+        .filter_map(|date| Some(time_prediction(date, predict_log_time(&history, date)?)))
+        .collect()
+}
+
+/// Replays a solver's history, predicting each result from only the results before it, to measure how predictable
+/// they are.
+///
+/// # Arguments
+///
+/// * `entries` - Every result recorded for the solver, in any order.
+///
+/// # Returns
+///
+/// A `PredictionOutcome` for every result after the first `MIN_PREDICTION_RESULTS`, oldest first.
+pub fn compute_prediction_outcomes(entries: &[ResultEntry]) -> Vec<PredictionOutcome> {
+    let history = dated_log_times(entries);
+
+    history
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &(date, actual_time, _))| {
+            Some(PredictionOutcome {
+                prediction: time_prediction(date, predict_log_time(&history[..i], date)?),
+                actual_time,
+            })
+        })
+        .collect()
+}
+
+/// Summarizes how accurate a list of predictions was.
+///
+/// # Returns
+///
+/// The `PredictionAccuracy` of `outcomes`, or `None` if there are none.
+pub fn summarize_prediction_accuracy(outcomes: &[PredictionOutcome]) -> Option<PredictionAccuracy> {
+    let errors = outcomes
+        .iter()
+        .map(|outcome| {
+            (f64::from(outcome.actual_time) / outcome.prediction.predicted_time - 1.0).abs()
+        })
+        .collect();
+    let within_interval = outcomes
+        .iter()
+        .filter(|outcome| {
+            let actual_time = f64::from(outcome.actual_time);
+            outcome.prediction.low_time <= actual_time
+                && actual_time <= outcome.prediction.high_time
+        })
+        .count();
+
+    Some(PredictionAccuracy {
+        num_predictions: outcomes.len(),
+        median_error: compute_median(errors)?,
+        interval_coverage: within_interval as f64 / outcomes.len() as f64,
+    })
+}
+
+/// Computes a user's streak and participation statistics.
+///
+/// A streak is current if it includes the most recent crossword date or the day before, so it isn't broken just
+/// because today's results haven't come in yet. A win is any result ranked first, including ties.
+///
+/// # Arguments
+///
+/// * `all_times` - Every result recorded for the user, in any order.
+/// * `most_recent_date` - The most recent date with at least one result from anyone.
+///
+/// # Returns
+///
+/// The user's `UserStats`, which are all zero if they have no results.
+pub fn compute_user_stats(all_times: &[ResultEntry], most_recent_date: NaiveDate) -> UserStats {
+    let mut days: Vec<(NaiveDate, bool)> = all_times
+        .iter()
+        .filter_map(|entry| {
+            NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d")
+                .ok()
+                .map(|date| (date, entry.rank == 1))
+        })
+        .collect();
+    days.sort();
+    days.dedup_by_key(|(date, _)| *date);
+
+    let (Some(&(first_date, _)), Some(&(last_date, _))) = (days.first(), days.last()) else {
+        return UserStats::default();
+    };
+
+    let mut stats = UserStats {
+        days_played: days.len(),
+        ..Default::default()
+    };
+    let mut play_streak = 0;
+    let mut win_streak = 0;
+    let mut previous_date: Option<NaiveDate> = None;
+    for &(date, won) in &days {
+        let days_since_previous = previous_date.map(|previous| (date - previous).num_days());
+        let consecutive = days_since_previous == Some(1);
+
+        play_streak = if consecutive { play_streak + 1 } else { 1 };
+        win_streak = match (won, consecutive) {
+            (false, _) => 0,
+            (true, true) => win_streak + 1,
+            (true, false) => 1,
+        };
+
+        stats.longest_play_streak = max(stats.longest_play_streak, play_streak);
+        stats.longest_win_streak = max(stats.longest_win_streak, win_streak);
+        stats.longest_gap = max(stats.longest_gap, days_since_previous.unwrap_or(1) - 1);
+        previous_date = Some(date);
+    }
+
+    if (most_recent_date - last_date).num_days() <= 1 {
+        stats.current_play_streak = play_streak;
+        stats.current_win_streak = win_streak;
+    }
+
+    let days_since_first_solve = max((most_recent_date - first_date).num_days() + 1, 1);
+    stats.participation_rate = (days.len() as f64 / days_since_first_solve as f64).min(1.0);
+
+    stats
+}
+
+/// Percent-encodes a value, e.g. a username, for use as a single URL path segment.
+pub fn encode_path_segment(value: &str) -> String {
+    utf8_percent_encode(value, PATH_SEGMENT).to_string()
+}
+
+/// Decodes a percent-encoded URL path segment.
+///
+/// # Returns
+///
+/// The decoded segment, or `None` if it doesn't decode to valid UTF-8.
+pub fn decode_path_segment(segment: &str) -> Option<String> {
+    percent_decode_str(segment)
+        .decode_utf8()
+        .ok()
+        .map(|decoded| decoded.into_owned())
+}
+
+/// Escapes the characters with special meaning in HTML text and attribute values.
+pub fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Links to a user's page, escaping and encoding the username.
+fn user_link(class: &str, username: &str) -> String {
+    format!(
+        "<a class=\"{class}\" href=\"/user/{}\">{}</a>",
+        encode_path_segment(username),
+        escape_html(username)
+    )
+}
+
+/// Describes the average time difference between two users as HTML linking to both users' pages.
+///
+/// # Arguments
+///
+/// * `user1` - The username of the first user.
+/// * `user2` - The username of the second user.
+/// * `avg_time_difference` - The average of `user1`'s time minus `user2`'s time, in seconds.
+///
+/// # Returns
+///
+/// The description as an HTML string.
+pub fn describe_time_difference(user1: &str, user2: &str, avg_time_difference: f64) -> String {
+    let speed_verb = if avg_time_difference < 0.0 {
+        "faster"
+    } else {
+        "slower"
+    };
+
+    format!(
+        "{} is {:.1} seconds {} than {} on average.",
+        user_link("user1", user1),
+        avg_time_difference.abs(),
+        speed_verb,
+        user_link("user2", user2),
+    )
+}
+
+/// Lists every crossword two users both solved, oldest first, with a running tally of who's ahead.
+///
+/// # Arguments
+///
+/// * `user1` - The first user's username.
+/// * `user1_results` - The first user's results, in any order.
+/// * `user2` - The second user's username.
+/// * `user2_results` - The second user's results, in any order.
+///
+/// # Returns
+///
+/// A vector of `HeadToHeadMatch` structs, sorted by date.
+pub fn compute_h2h_matches(
+    user1: &str,
+    user1_results: &[ResultEntry],
+    user2: &str,
+    user2_results: &[ResultEntry],
+) -> Vec<HeadToHeadMatch> {
+    let user2_times: HashMap<&str, i32> = user2_results
+        .iter()
+        .map(|entry| (entry.date.as_str(), entry.time))
+        .collect();
+
+    let mut shared: Vec<(&str, i32, i32)> = user1_results
+        .iter()
+        .filter_map(|entry| {
+            user2_times
+                .get(entry.date.as_str())
+                .map(|&user2_time| (entry.date.as_str(), entry.time, user2_time))
+        })
+        .collect();
+    shared.sort_by_key(|(date, _, _)| *date);
+
+    let mut win_difference = 0;
+    shared
+        .into_iter()
+        .map(|(date, user1_time, user2_time)| {
+            let (winner, win_delta) = match user1_time.cmp(&user2_time) {
+                Ordering::Less => (Some(user1), 1),
+                Ordering::Greater => (Some(user2), -1),
+                Ordering::Equal => (None, 0),
+            };
+            win_difference += win_delta;
+
+            HeadToHeadMatch {
+                date: date.to_string(),
+                user1_time,
+                user2_time,
+                margin: user1_time - user2_time,
+                winner: winner.map(str::to_string),
+                win_difference,
+            }
+        })
+        .collect()
+}
+
+/// Summarizes two users' record over every crossword they both solved.
+///
+/// # Arguments
+///
+/// * `user1` - The first user's username.
+/// * `user2` - The second user's username.
+/// * `matches` - The crosswords both users solved, as returned by `compute_h2h_matches`.
+///
+/// # Returns
+///
+/// The `HeadToHeadData` from `user1`'s point of view, or `None` if the users haven't solved the same crossword.
+pub fn compute_h2h_stats(
+    user1: &str,
+    user2: &str,
+    matches: &[HeadToHeadMatch],
+) -> Option<HeadToHeadData> {
+    if matches.is_empty() {
+        return None;
+    }
+
+    let mut data = HeadToHeadData {
+        user1: user1.to_string(),
+        user2: user2.to_string(),
+        total_matches: matches.len() as i32,
+        ..Default::default()
+    };
+    for h2h_match in matches {
+        match h2h_match.margin.cmp(&0) {
+            Ordering::Less => data.wins_user1 += 1,
+            Ordering::Greater => data.wins_user2 += 1,
+            Ordering::Equal => data.ties += 1,
+        }
+    }
+    data.avg_time_difference = matches
+        .iter()
+        .map(|h2h_match| f64::from(h2h_match.margin))
+        .sum::<f64>()
+        / f64::from(data.total_matches);
+    data.time_diff_description = describe_time_difference(user1, user2, data.avg_time_difference);
+
+    Some(data)
+}
+
+/// Computes the head-to-head record of every pair of players in a single pass over the results.
+///
+/// # Arguments
+///
+/// * `results` - Every result. Results from players not in `usernames` are ignored.
+/// * `usernames` - The players to include, in the order they should appear in the matrix.
+///
+/// # Returns
+///
+/// A `HeadToHeadMatrix` whose rows and columns follow the order of `usernames`.
+pub fn compute_h2h_matrix(results: &[ResultEntry], usernames: Vec<String>) -> HeadToHeadMatrix {
+    let mut records = vec![vec![HeadToHeadRecord::default(); usernames.len()]; usernames.len()];
+
+    let indices: HashMap<&str, usize> = usernames
+        .iter()
+        .enumerate()
+        .map(|(i, username)| (username.as_str(), i))
+        .collect();
+    let mut times_by_date: BTreeMap<&str, Vec<(usize, i32)>> = BTreeMap::new();
+    for entry in results {
+        if let Some(&i) = indices.get(entry.username.as_str()) {
+            times_by_date
+                .entry(entry.date.as_str())
+                .or_default()
+                .push((i, entry.time));
+        }
+    }
+
+    for times in times_by_date.values() {
+        for &(i, time) in times {
+            for &(j, other_time) in times.iter().filter(|(j, _)| *j != i) {
+                let record = &mut records[i][j];
+                match time.cmp(&other_time) {
+                    Ordering::Less => record.wins += 1,
+                    Ordering::Greater => record.losses += 1,
+                    Ordering::Equal => record.ties += 1,
+                }
+                record.total_matches += 1;
+            }
+        }
+    }
+
+    for record in records.iter_mut().flatten() {
+        record.win_rate = (record.total_matches > 0).then(|| {
+            (f64::from(record.wins) + f64::from(record.ties) / 2.0)
+                / f64::from(record.total_matches)
+        });
+    }
+
+    HeadToHeadMatrix { usernames, records }
+}
+
+/// Parses the podium's query parameters into a `PodiumFilter`. Empty values are ignored.
+///
+/// # Arguments
+///
+/// * `params` - The query parameters as key-value pairs: `limit`, `weekday` (e.g. "sat" or "saturday"), `month`
+///   (1 to 12) and `year`.
+///
+/// # Returns
+///
+/// A `Result` containing the `PodiumFilter`, or a `PodiumFilterError` describing the first invalid parameter.
+pub fn parse_podium_filter<K: AsRef<str>, V: AsRef<str>>(
+    params: impl IntoIterator<Item = (K, V)>,
+) -> Result<PodiumFilter, PodiumFilterError> {
+    let mut filter = PodiumFilter::default();
+
+    for (key, value) in params {
+        let value = value.as_ref().trim();
+        if value.is_empty() {
+            continue;
+        }
+
+        match key.as_ref() {
+            "limit" => {
+                filter.limit = value
+                    .parse()
+                    .ok()
+                    .filter(|limit| (1..=MAX_PODIUM_LIMIT).contains(limit))
+                    .ok_or(PodiumFilterError::InvalidLimit)?;
+            }
+            "weekday" => {
+                filter.weekday = Some(
+                    value
+                        .parse()
+                        .map_err(|_| PodiumFilterError::InvalidWeekday)?,
+                );
+            }
+            "month" => {
+                filter.month = Some(
+                    value
+                        .parse()
+                        .ok()
+                        .filter(|month| (1..=12).contains(month))
+                        .ok_or(PodiumFilterError::InvalidMonth)?,
+                );
+            }
+            "year" => {
+                filter.year = Some(value.parse().map_err(|_| PodiumFilterError::InvalidYear)?);
+            }
+            key => return Err(PodiumFilterError::UnknownParameter(key.to_string())),
+        }
+    }
+
+    Ok(filter)
+}
+
+/// Checks whether a result's date passes a `PodiumFilter`.
+///
+/// # Arguments
+///
+/// * `filter` - The filter to check against. Its limit is ignored.
+/// * `date` - A string representing the date in "YYYY-MM-DD" format.
+///
+/// # Returns
+///
+/// `true` if the date is valid and matches every filter that is set.
+pub fn podium_filter_matches(filter: &PodiumFilter, date: &str) -> bool {
+    let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+        return false;
+    };
+
+    filter
+        .weekday
+        .is_none_or(|weekday| date.weekday() == weekday)
+        && filter.month.is_none_or(|month| date.month() == month)
+        && filter.year.is_none_or(|year| date.year() == year)
+}
+
+/// Describes a `PodiumFilter` as a page title, e.g. "Top 10 Saturday Times in March 2025".
+pub fn describe_podium_filter(filter: &PodiumFilter) -> String {
+    let weekday = match filter.weekday {
+        Some(weekday) => format!("{} ", weekday_name(weekday)),
+        None => String::new(),
+    };
+
+    let month = filter
+        .month
+        .and_then(|month| Month::try_from(month as u8).ok())
+        .map(|month| month.name());
+
+    let period = match (month, filter.year) {
+        (Some(month), Some(year)) => format!(" in {month} {year}"),
+        (Some(month), None) => format!(" in {month}"),
+        (None, Some(year)) => format!(" in {year}"),
+        (None, None) => String::new(),
+    };
+
+    format!("Top {} {weekday}Times{period}", filter.limit)
+}
+
+/// The full English name of a weekday, e.g. "Saturday".
+pub fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+/// Compares a provided secret against the expected one without short-circuiting on the first mismatch.
+///
+/// # Arguments
+///
+/// * `provided` - The secret supplied by the client.
+/// * `expected` - The secret configured for the worker.
+///
+/// # Returns
+///
+/// `true` if both secrets are identical.
+pub fn secrets_match(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_podium_filter() {
+        assert_eq!(
+            parse_podium_filter([
+                ("limit", "5"),
+                ("weekday", "Saturday"),
+                ("month", ""),
+                ("year", "2025")
+            ]),
+            Ok(PodiumFilter {
+                limit: 5,
+                weekday: Some(Weekday::Sat),
+                month: None,
+                year: Some(2025),
+            })
+        );
+        assert_eq!(
+            parse_podium_filter(Vec::<(&str, &str)>::new()),
+            Ok(PodiumFilter::default())
+        );
+        assert_eq!(
+            parse_podium_filter([("limit", "0")]),
+            Err(PodiumFilterError::InvalidLimit)
+        );
+        assert_eq!(
+            parse_podium_filter([("weekday", "caturday")]),
+            Err(PodiumFilterError::InvalidWeekday)
+        );
+        assert_eq!(
+            parse_podium_filter([("month", "13")]),
+            Err(PodiumFilterError::InvalidMonth)
+        );
+    }
+
+    #[test]
+    fn test_podium_filter_matches_and_description() {
+        let filter = PodiumFilter {
+            weekday: Some(Weekday::Sat),
+            month: Some(10),
+            ..Default::default()
+        };
+
+        assert!(podium_filter_matches(&filter, "2026-10-10"));
+        assert!(!podium_filter_matches(&filter, "2026-10-11"));
+        assert!(!podium_filter_matches(&filter, "2026-11-07"));
+        assert!(podium_filter_matches(
+            &PodiumFilter::default(),
+            "2026-11-07"
+        ));
+        assert_eq!(
+            describe_podium_filter(&filter),
+            "Top 10 Saturday Times in October"
+        );
+        assert_eq!(
+            describe_podium_filter(&PodiumFilter::default()),
+            "Top 10 Times"
+        );
+    }
+
+    #[test]
+    fn test_filter_to_recent_days() {
+        let entries = vec![
+            ResultEntry {
+                date: "2023-10-25".to_string(),
+                ..Default::default()
+            },
+            ResultEntry {
+                date: "2023-10-27".to_string(),
+                ..Default::default()
+            },
+            ResultEntry {
+                date: "2023-10-26".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let dates: Vec<String> = filter_to_recent_days(&entries, 2)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.date)
+            .collect();
+
+        assert_eq!(dates, vec!["2023-10-27", "2023-10-26"]);
+        assert!(filter_to_recent_days(&[], 2).unwrap().is_empty());
+
+        let corrupt = [ResultEntry {
+            date: "2023-10-32".to_string(),
+            ..Default::default()
+        }];
+        assert_eq!(
+            filter_to_recent_days(&corrupt, 2).unwrap_err(),
+            LeaderboardWindowError::InvalidResultDate(String::from("2023-10-32"))
+        );
+    }
+
+    #[test]
+    fn test_compute_moving_averages_with_partial_averages() {
+        let entries = vec![
+            ResultEntry {
+                date: "2023-10-25".to_string(),
+                time: 100,
+                ..Default::default()
+            },
+            ResultEntry {
+                date: "2023-10-26".to_string(),
+                time: 120,
+                ..Default::default()
+            },
+            ResultEntry {
+                date: "2023-10-27".to_string(),
+                time: 110,
+                ..Default::default()
+            },
+        ];
+
+        let (dates, averages) = compute_moving_averages(&entries, 2, true);
+
+        assert_eq!(dates, vec!["2023-10-25", "2023-10-26", "2023-10-27"]);
+        assert_eq!(averages, vec![100, 110, 115]);
+    }
+
+    #[test]
+    fn test_compute_moving_averages_without_partial_averages() {
+        let entries = vec![
+            ResultEntry {
+                date: "2023-10-25".to_string(),
+                time: 100,
+                ..Default::default()
+            },
+            ResultEntry {
+                date: "2023-10-26".to_string(),
+                time: 120,
+                ..Default::default()
+            },
+            ResultEntry {
+                date: "2023-10-27".to_string(),
+                time: 110,
+                ..Default::default()
+            },
+        ];
+
+        let (dates, averages) = compute_moving_averages(&entries, 2, false);
+
+        assert_eq!(dates, vec!["2023-10-26", "2023-10-27"]);
+        assert_eq!(averages, vec![110, 115]);
+    }
+
+    #[test]
+    fn test_compute_moving_averages_with_empty_input() {
         let entries: Vec<ResultEntry> = Vec::new();
 
         let (dates, averages) = compute_moving_averages(&entries, 2, true);
@@ -368,6 +2374,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_generate_elo_plot_html_with_empty_history() {
+        let result = generate_elo_plot_html(&[]);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "Plotting error: User doesn't have enough entries to generate plot"
+        );
+    }
+
     #[test]
     fn test_generate_box_plot_html_with_no_user_entries() {
         let all_user_entries: Vec<&mut [ResultEntry]> = vec![];
@@ -393,4 +2410,766 @@ mod tests {
             "Plotting error: User doesn't have enough entries to generate plot"
         );
     }
+
+    fn submission(date: &str, username: &str, time: i32) -> Submission {
+        Submission {
+            date: date.to_string(),
+            username: username.to_string(),
+            time,
+        }
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 16).unwrap()
+    }
+
+    #[test]
+    fn test_validate_submission_accepts_valid_submission() {
+        let entry = validate_submission(submission("2026-10-16", " alice ", 42), today()).unwrap();
+
+        assert_eq!(entry.date, "2026-10-16");
+        assert_eq!(entry.username, "alice");
+        assert_eq!(entry.time, 42);
+        assert_eq!(entry.rank, 0);
+    }
+
+    #[test]
+    fn test_validate_submission_accepts_next_days_puzzle() {
+        assert!(validate_submission(submission("2026-10-17", "alice", 42), today()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_submission_rejects_invalid_fields() {
+        assert!(matches!(
+            validate_submission(submission("10/16/2026", "alice", 42), today()),
+            Err(SubmissionError::InvalidDate)
+        ));
+        assert!(matches!(
+            validate_submission(submission("2026-10-18", "alice", 42), today()),
+            Err(SubmissionError::FutureDate)
+        ));
+        assert!(matches!(
+            validate_submission(submission("2026-10-16", "   ", 42), today()),
+            Err(SubmissionError::InvalidUsername)
+        ));
+        assert!(matches!(
+            validate_submission(submission("2026-10-16", "alice", 0), today()),
+            Err(SubmissionError::InvalidTime)
+        ));
+    }
+
+    #[test]
+    fn test_compute_ranks_with_ties() {
+        let mut entries: Vec<ResultEntry> = [50, 30, 40, 30]
+            .into_iter()
+            .map(|time| ResultEntry {
+                time,
+                ..Default::default()
+            })
+            .collect();
+
+        compute_ranks(&mut entries);
+
+        let times: Vec<i32> = entries.iter().map(|entry| entry.time).collect();
+        let ranks: Vec<i32> = entries.iter().map(|entry| entry.rank).collect();
+        assert_eq!(times, vec![30, 30, 40, 50]);
+        assert_eq!(ranks, vec![1, 1, 3, 4]);
+    }
+
+    #[test]
+    fn test_corrections() {
+        let result = |username: &str, time: i32, rank: i32| ResultEntry {
+            date: String::from("2026-10-12"),
+            username: username.to_string(),
+            time,
+            rank,
+        };
+
+        let reranked = rerank_results(vec![
+            result("alice", 35, 1),
+            result("carol", 40, 2),
+            result("bob", 30, 3),
+            result("dave", 66, 4),
+        ]);
+        let ranks: Vec<(&str, i32)> = reranked
+            .iter()
+            .map(|entry| (entry.username.as_str(), entry.rank))
+            .collect();
+        assert_eq!(ranks, vec![("bob", 1), ("alice", 2), ("carol", 3)]);
+
+        assert_eq!(parse_corrected_time(" 42 ").unwrap(), 42);
+        assert_eq!(parse_corrected_time("1:05").unwrap(), 65);
+        assert!(matches!(
+            parse_corrected_time("fast"),
+            Err(CorrectionError::InvalidTime)
+        ));
+
+        let alice = vec![result("alice", 35, 1)];
+        let bob = vec![result("bob", 49, 3)];
+        assert!(validate_merge("alice", "alice2", &alice, &[]).is_ok());
+        assert!(matches!(
+            validate_merge("alice", "alice", &alice, &alice),
+            Err(CorrectionError::SameUser(_))
+        ));
+        assert!(matches!(
+            validate_merge("alice", "bob", &alice, &bob),
+            Err(CorrectionError::MergeConflict(..))
+        ));
+    }
+
+    #[test]
+    fn test_secrets_match() {
+        assert!(secrets_match("hunter2", "hunter2"));
+        assert!(!secrets_match("hunter3", "hunter2"));
+        assert!(!secrets_match("hunter", "hunter2"));
+        assert!(!secrets_match("", "hunter2"));
+    }
+
+    #[test]
+    fn test_path_segment_round_trip() {
+        for username in ["alice", "Mary Jane", "O'Brien & Co", "🦆 duck", "Zoë/Renée"] {
+            let encoded = encode_path_segment(username);
+            assert!(!encoded.contains(['/', ' ', '&', '\'']), "{encoded}");
+            assert_eq!(decode_path_segment(&encoded).as_deref(), Some(username));
+        }
+
+        assert_eq!(encode_path_segment("Mary Jane"), "Mary%20Jane");
+        assert_eq!(encode_path_segment("🦆"), "%F0%9F%A6%86");
+        assert_eq!(decode_path_segment("%FF"), None);
+    }
+
+    #[test]
+    fn test_describe_time_difference_escapes_usernames() {
+        let description = describe_time_difference("<b>&</b>", "🦆", -2.5);
+
+        assert_eq!(
+            description,
+            "<a class=\"user1\" href=\"/user/%3Cb%3E%26%3C%2Fb%3E\">&lt;b&gt;&amp;&lt;/b&gt;</a> is 2.5 \
+             seconds faster than <a class=\"user2\" href=\"/user/%F0%9F%A6%86\">🦆</a> on average."
+        );
+    }
+
+    #[test]
+    fn test_compute_h2h_matches() {
+        let entry = |date: &str, time| ResultEntry {
+            date: date.to_string(),
+            time,
+            ..Default::default()
+        };
+        let alice = vec![
+            entry("2026-10-03", 50),
+            entry("2026-10-01", 30),
+            entry("2026-10-02", 20),
+            entry("2026-10-04", 40),
+        ];
+        let bob = vec![
+            entry("2026-10-01", 40),
+            entry("2026-10-03", 45),
+            entry("2026-10-04", 40),
+            entry("2026-10-05", 10),
+        ];
+
+        let matches = compute_h2h_matches("alice", &alice, "bob", &bob);
+
+        let summary: Vec<(&str, i32, Option<&str>, i32)> = matches
+            .iter()
+            .map(|m| {
+                (
+                    m.date.as_str(),
+                    m.margin,
+                    m.winner.as_deref(),
+                    m.win_difference,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("2026-10-01", -10, Some("alice"), 1),
+                ("2026-10-03", 5, Some("bob"), 0),
+                ("2026-10-04", 0, None, 0),
+            ]
+        );
+        let stats = compute_h2h_stats("alice", "bob", &matches).unwrap();
+        assert_eq!(
+            (
+                stats.wins_user1,
+                stats.wins_user2,
+                stats.ties,
+                stats.total_matches
+            ),
+            (1, 1, 1, 3)
+        );
+        assert!((stats.avg_time_difference + 5.0 / 3.0).abs() < 1e-9);
+        assert!(compute_h2h_stats("alice", "bob", &[]).is_none());
+        assert!(generate_h2h_plot_html(&matches).is_ok());
+        assert!(generate_h2h_plot_html(&[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_compared_users() {
+        assert_eq!(parse_compared_users(Vec::<&str>::new()), Ok(Vec::new()));
+        assert_eq!(
+            parse_compared_users(["carol, alice,,carol", "bob"]),
+            Ok(vec![
+                String::from("carol"),
+                String::from("alice"),
+                String::from("bob")
+            ])
+        );
+        assert_eq!(
+            parse_compared_users(["alice, alice"]),
+            Err(ComparisonError::TooFewUsers)
+        );
+        assert_eq!(
+            parse_compared_users(["a,b,c,d,e,f,g,h,i"]),
+            Err(ComparisonError::TooManyUsers)
+        );
+    }
+
+    #[test]
+    fn test_compute_win_probability_matrix() {
+        let matrix = compute_win_probability_matrix(&[(30.0, 2.0), (25.0, 2.0), (25.0, 2.0)]);
+
+        assert_eq!(matrix[0][0], None);
+        assert!(matrix[0][1].unwrap() > 0.5);
+        assert!((matrix[1][2].unwrap() - 0.5).abs() < 1e-6);
+        assert!((matrix[0][1].unwrap() + matrix[1][0].unwrap() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_compute_h2h_matrix() {
+        let entry = |date: &str, username: &str, time| ResultEntry {
+            date: date.to_string(),
+            username: username.to_string(),
+            time,
+            rank: 0,
+        };
+        let results = vec![
+            entry("2026-10-01", "alice", 30),
+            entry("2026-10-01", "bob", 40),
+            entry("2026-10-01", "carol", 20),
+            entry("2026-10-02", "alice", 25),
+            entry("2026-10-02", "bob", 25),
+            entry("2026-10-03", "bob", 10),
+        ];
+
+        let matrix = compute_h2h_matrix(
+            &results,
+            vec![
+                String::from("alice"),
+                String::from("bob"),
+                String::from("dave"),
+            ],
+        );
+
+        assert_eq!(
+            matrix.records[0][1],
+            HeadToHeadRecord {
+                wins: 1,
+                losses: 0,
+                ties: 1,
+                total_matches: 2,
+                win_rate: Some(0.75),
+            }
+        );
+        assert_eq!(matrix.records[1][0].losses, 1);
+        assert_eq!(matrix.records[1][0].win_rate, Some(0.25));
+        assert_eq!(matrix.records[0][0], HeadToHeadRecord::default());
+        assert_eq!(matrix.records[0][2].win_rate, None);
+    }
+
+    #[test]
+    fn test_predict_solve_times() {
+        let entry = |date: NaiveDate, time: i32| ResultEntry {
+            date: date.format("%Y-%m-%d").to_string(),
+            time,
+            ..Default::default()
+        };
+
+        // Ten weeks starting on Monday 2026-08-03, with Saturdays three times slower than other days.
+        let first_date = NaiveDate::from_ymd_opt(2026, 8, 3).unwrap();
+        let entries: Vec<ResultEntry> = first_date
+            .iter_days()
+            .take(70)
+            .enumerate()
+            .map(|(i, date)| {
+                let time = if date.weekday() == Weekday::Sat {
+                    90
+                } else {
+                    30
+                };
+                entry(date, time + if i % 2 == 0 { 2 } else { -2 })
+            })
+            .collect();
+
+        let next_date = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        let predictions = predict_solve_times(&entries, next_date);
+        assert_eq!(predictions.len(), 7);
+        assert_eq!(predictions[0].date, "2026-10-12");
+        assert_eq!(predictions[0].weekday, "Monday");
+        assert!((predictions[0].predicted_time - 30.0).abs() < 3.0);
+        assert!(predictions[5].predicted_time > 2.0 * predictions[0].predicted_time);
+        for prediction in &predictions {
+            assert!(prediction.low_time < prediction.predicted_time);
+            assert!(prediction.predicted_time < prediction.high_time);
+        }
+
+        // Someone getting 2% faster every day is predicted to keep improving.
+        let improving: Vec<ResultEntry> = first_date
+            .iter_days()
+            .take(30)
+            .enumerate()
+            .map(|(i, date)| entry(date, (100.0 * 0.98f64.powi(i as i32)).round() as i32))
+            .collect();
+        let prediction = &predict_solve_times(&improving, first_date + Duration::days(30))[0];
+        assert!(prediction.predicted_time < 60.0);
+
+        assert!(predict_solve_times(&entries[..4], next_date).is_empty());
+
+        let outcomes = compute_prediction_outcomes(&entries);
+        assert_eq!(outcomes.len(), 65);
+        assert_eq!(outcomes[0].prediction.date, "2026-08-08");
+        assert_eq!(outcomes[0].actual_time, 88);
+
+        let accuracy = summarize_prediction_accuracy(&outcomes).unwrap();
+        assert_eq!(accuracy.num_predictions, 65);
+        assert!(accuracy.median_error < 0.2);
+        assert!(accuracy.interval_coverage > 0.5);
+        assert_eq!(summarize_prediction_accuracy(&[]), None);
+    }
+
+    #[test]
+    fn test_compute_user_stats() {
+        let entry = |date: &str, rank: i32| ResultEntry {
+            date: date.to_string(),
+            rank,
+            ..Default::default()
+        };
+        let most_recent_date = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+
+        let stats = compute_user_stats(
+            &[
+                entry("2026-10-01", 1),
+                entry("2026-10-02", 1),
+                entry("2026-10-03", 1),
+                entry("2026-10-04", 2),
+                entry("2026-10-08", 1),
+                entry("2026-10-10", 1),
+                entry("2026-10-11", 1),
+            ],
+            most_recent_date,
+        );
+        assert_eq!(
+            stats,
+            UserStats {
+                days_played: 7,
+                current_play_streak: 2,
+                longest_play_streak: 4,
+                current_win_streak: 2,
+                longest_win_streak: 3,
+                participation_rate: 7.0 / 12.0,
+                longest_gap: 3,
+            }
+        );
+
+        let lapsed = compute_user_stats(&[entry("2026-10-01", 1)], most_recent_date);
+        assert_eq!(
+            (lapsed.current_play_streak, lapsed.longest_play_streak),
+            (0, 1)
+        );
+        assert_eq!(
+            compute_user_stats(&[], most_recent_date),
+            UserStats::default()
+        );
+    }
+
+    #[test]
+    fn test_detect_suspicious_results() {
+        let entry = |date: &str, username: &str, time: i32| ResultEntry {
+            date: date.to_string(),
+            username: username.to_string(),
+            time,
+            ..Default::default()
+        };
+
+        // alice, bob and carol solve in about a minute every day from 2026-10-01 to 2026-10-12.
+        let mut results: Vec<ResultEntry> = (1..=12)
+            .flat_map(|day| {
+                let date = format!("2026-10-{day:02}");
+                [
+                    entry(&date, "alice", 55 + day % 3),
+                    entry(&date, "bob", 60 + day % 4),
+                    entry(&date, "carol", 65 + day % 5),
+                ]
+            })
+            .collect();
+        results.extend([
+            entry("2026-10-13", "alice", 15),
+            entry("2026-10-13", "bob", 62),
+            entry("2026-10-13", "carol", 66),
+            entry("2026-10-14", "alice", 56),
+            entry("2026-10-14", "bob", 61),
+            entry("2026-10-14", "carol", 67),
+            entry("2026-10-14", "erin", 20),
+            entry("2026-10-15", "frank", 5),
+        ]);
+
+        let flags = detect_suspicious_results(&results);
+        let summary: Vec<(&str, &str, FlagReason)> = flags
+            .iter()
+            .map(|flag| (flag.date.as_str(), flag.username.as_str(), flag.reason))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("2026-10-13", "alice", FlagReason::PersonalOutlier),
+                ("2026-10-14", "erin", FlagReason::FieldOutlier),
+                ("2026-10-15", "frank", FlagReason::ImpossibleTime),
+            ]
+        );
+        assert_eq!(flags[2].detail, "Solved in under 8 seconds");
+        assert!(flags
+            .iter()
+            .all(|flag| flag.status == ModerationStatus::Pending));
+
+        let mut approved = flags[0].clone();
+        approved.status = ModerationStatus::Approved;
+        let rated = exclude_flagged_results(results.clone(), &[approved, flags[1].clone()]);
+        assert_eq!(rated.len(), results.len() - 1);
+        assert!(!rated.iter().any(|entry| entry.username == "erin"));
+    }
+
+    #[test]
+    fn test_compute_weekday_stats() {
+        let entry = |date: &str, time: i32, rank: i32| ResultEntry {
+            date: date.to_string(),
+            time,
+            rank,
+            ..Default::default()
+        };
+
+        // 2026-10-05 and 2026-10-12 are Mondays, 2026-10-10 and 2026-10-17 are Saturdays.
+        let data = build_user_data(vec![
+            entry("2026-10-05", 20, 1),
+            entry("2026-10-12", 30, 2),
+            entry("2026-10-10", 100, 1),
+            entry("2026-10-17", 90, 3),
+            entry("2026-10-24", 140, 1),
+        ]);
+        assert_eq!(data.times_excluding_saturday.len(), 2);
+
+        let stats = compute_weekday_stats(&data);
+        assert_eq!(
+            stats,
+            vec![
+                WeekdayStats {
+                    weekday: "Monday",
+                    count: 2,
+                    average_time: 25.0,
+                    median_time: 25.0,
+                    best_time: 20,
+                    win_rate: 0.5,
+                },
+                WeekdayStats {
+                    weekday: "Saturday",
+                    count: 3,
+                    average_time: 110.0,
+                    median_time: 100.0,
+                    best_time: 90,
+                    win_rate: 2.0 / 3.0,
+                },
+            ]
+        );
+
+        assert!(generate_weekday_box_plot_html(&data.times_by_weekday)
+            .unwrap()
+            .contains("weekday-plot"));
+        assert!(generate_weekday_box_plot_html(&Default::default()).is_err());
+    }
+
+    #[test]
+    fn test_compute_puzzle_difficulties() {
+        let entry = |date: &str, username: &str, time: i32| ResultEntry {
+            date: date.to_string(),
+            username: username.to_string(),
+            time,
+            ..Default::default()
+        };
+
+        let results = vec![
+            entry("2026-10-01", "alice", 30),
+            entry("2026-10-01", "bob", 60),
+            entry("2026-10-02", "alice", 30),
+            entry("2026-10-02", "bob", 60),
+            entry("2026-10-03", "alice", 60),
+            entry("2026-10-03", "bob", 150),
+            entry("2026-10-03", "carol", 10),
+        ];
+
+        let difficulties = compute_puzzle_difficulties(&results);
+        let summary: Vec<(&str, f64, &str, usize)> = difficulties
+            .iter()
+            .map(|d| {
+                (
+                    d.date.as_str(),
+                    d.difficulty,
+                    d.label.as_str(),
+                    d.num_solvers,
+                )
+            })
+            .collect();
+
+        // carol only has one result, so she counts as a solver but not towards the difficulty.
+        assert_eq!(
+            summary,
+            vec![
+                ("2026-10-01", 1.0, "Average", 2),
+                ("2026-10-02", 1.0, "Average", 2),
+                ("2026-10-03", 2.25, "Very Hard", 3),
+            ]
+        );
+        assert_eq!(difficulties[2].median_time, 60.0);
+    }
+
+    #[test]
+    fn test_score_results() {
+        let entry = |date: &str, username: &str, time: i32| ResultEntry {
+            date: date.to_string(),
+            username: username.to_string(),
+            time,
+            ..Default::default()
+        };
+
+        let results = vec![
+            entry("2026-10-01", "alice", 20),
+            entry("2026-10-01", "bob", 40),
+            entry("2026-10-01", "carol", 80),
+            entry("2026-10-02", "alice", 40),
+            entry("2026-10-02", "bob", 40),
+            entry("2026-10-03", "alice", 80),
+        ];
+
+        let scores: Vec<(Option<f64>, Option<f64>)> = score_results(&results)
+            .into_iter()
+            .map(|scored| (scored.field_score, scored.personal_score))
+            .collect();
+
+        // On 2026-10-01 the log times are evenly spaced, so bob is exactly average and alice and carol are
+        // sqrt(3/2) standard deviations either side. 2026-10-02 and 2026-10-03 have too few solvers to score.
+        let spread = 1.5_f64.sqrt();
+        assert!((scores[0].0.unwrap() - spread).abs() < 1e-9);
+        assert!(scores[1].0.unwrap().abs() < 1e-9);
+        assert!((scores[2].0.unwrap() + spread).abs() < 1e-9);
+        assert_eq!(scores[3].0, None);
+
+        // alice's log times are also evenly spaced, and bob and carol don't have enough results.
+        assert!((scores[0].1.unwrap() - spread).abs() < 1e-9);
+        assert!(scores[3].1.unwrap().abs() < 1e-9);
+        assert!((scores[5].1.unwrap() + spread).abs() < 1e-9);
+        assert_eq!((scores[1].1, scores[2].1), (None, None));
+    }
+
+    #[test]
+    fn test_parse_leaderboard_window() {
+        let date = |day: u32| NaiveDate::from_ymd_opt(2025, 1, day);
+
+        assert_eq!(
+            parse_leaderboard_window("all", None, None),
+            Ok(LeaderboardWindow::All)
+        );
+        assert_eq!(
+            parse_leaderboard_window("last_7", None, Some("")),
+            Ok(LeaderboardWindow::LastDays(7))
+        );
+        assert_eq!(
+            parse_leaderboard_window("this_month", None, None),
+            Ok(LeaderboardWindow::ThisMonth)
+        );
+        assert_eq!(
+            parse_leaderboard_window("all", Some("2025-01-01"), Some("2025-01-31")),
+            Ok(LeaderboardWindow::Range(date(1), date(31)))
+        );
+        assert_eq!(
+            parse_leaderboard_window("all", Some("2025-01-01"), None),
+            Ok(LeaderboardWindow::Range(date(1), None))
+        );
+
+        assert_eq!(
+            parse_leaderboard_window("results_rust", None, None),
+            Err(LeaderboardWindowError::UnknownWindow(String::from(
+                "results_rust"
+            )))
+        );
+        assert_eq!(
+            parse_leaderboard_window("last_0", None, None),
+            Err(LeaderboardWindowError::InvalidDays)
+        );
+        assert_eq!(
+            parse_leaderboard_window("all", Some("2025-13-01"), None),
+            Err(LeaderboardWindowError::InvalidDate(String::from(
+                "2025-13-01"
+            )))
+        );
+        assert_eq!(
+            parse_leaderboard_window("all", Some("2025-02-01"), Some("2025-01-01")),
+            Err(LeaderboardWindowError::EmptyRange)
+        );
+        assert_eq!(
+            parse_leaderboard_window("last_30", Some("2025-01-01"), None),
+            Err(LeaderboardWindowError::RangeWithWindow)
+        );
+    }
+
+    #[test]
+    fn test_filter_to_window() {
+        let entry = |date: &str| ResultEntry {
+            date: date.to_string(),
+            ..Default::default()
+        };
+        let results = vec![
+            entry("2024-12-31"),
+            entry("2025-01-15"),
+            entry("2025-02-01"),
+            entry("2025-02-10"),
+        ];
+        let today = NaiveDate::from_ymd_opt(2025, 2, 12).unwrap();
+        let dates = |window| -> Vec<String> {
+            filter_to_window(&results, window, today)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.date)
+                .collect()
+        };
+
+        assert_eq!(dates(LeaderboardWindow::All).len(), 4);
+        assert_eq!(
+            dates(LeaderboardWindow::LastDays(10)),
+            vec!["2025-02-01", "2025-02-10"]
+        );
+        assert_eq!(
+            dates(LeaderboardWindow::ThisMonth),
+            vec!["2025-02-01", "2025-02-10"]
+        );
+        assert_eq!(dates(LeaderboardWindow::ThisYear).len(), 3);
+        assert_eq!(
+            dates(LeaderboardWindow::Range(
+                NaiveDate::from_ymd_opt(2025, 1, 1),
+                NaiveDate::from_ymd_opt(2025, 2, 1)
+            )),
+            vec!["2025-01-15", "2025-02-01"]
+        );
+    }
+
+    fn season(id: &str, start_date: &str, end_date: &str) -> Season {
+        Season {
+            id: id.to_string(),
+            name: id.to_string(),
+            start_date: start_date.to_string(),
+            end_date: end_date.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_list_seasons() {
+        let first_date = NaiveDate::from_ymd_opt(2025, 11, 20).unwrap();
+        let today = NaiveDate::from_ymd_opt(2026, 2, 3).unwrap();
+
+        let quarterly = list_seasons(&SeasonSchedule::Quarterly, first_date, today).unwrap();
+        assert_eq!(
+            quarterly,
+            vec![
+                Season {
+                    id: String::from("2026-q1"),
+                    name: String::from("Q1 2026"),
+                    start_date: String::from("2026-01-01"),
+                    end_date: String::from("2026-03-31"),
+                },
+                Season {
+                    id: String::from("2025-q4"),
+                    name: String::from("Q4 2025"),
+                    start_date: String::from("2025-10-01"),
+                    end_date: String::from("2025-12-31"),
+                },
+            ]
+        );
+
+        let monthly = list_seasons(&SeasonSchedule::Monthly, first_date, today).unwrap();
+        let ids: Vec<&str> = monthly.iter().map(|season| season.id.as_str()).collect();
+        assert_eq!(ids, vec!["2026-02", "2026-01", "2025-12", "2025-11"]);
+        assert_eq!(monthly[0].name, "February 2026");
+        assert_eq!(monthly[0].end_date, "2026-02-28");
+
+        let custom = SeasonSchedule::Custom(vec![
+            season("winter", "2025-12-01", "2026-02-28"),
+            season("spring", "2026-03-01", "2026-05-31"),
+        ]);
+        let ids: Vec<String> = list_seasons(&custom, first_date, today)
+            .unwrap()
+            .into_iter()
+            .map(|season| season.id)
+            .collect();
+        assert_eq!(ids, vec!["spring", "winter"]);
+
+        let invalid = |seasons| list_seasons(&SeasonSchedule::Custom(seasons), first_date, today);
+        assert_eq!(
+            invalid(vec![season("winter", "2025-12-01", "2026-02-30")]),
+            Err(SeasonError::InvalidDate(String::from("winter")))
+        );
+        assert_eq!(
+            invalid(vec![season("winter", "2026-02-28", "2025-12-01")]),
+            Err(SeasonError::EmptySeason(String::from("winter")))
+        );
+        assert_eq!(
+            invalid(vec![
+                season("winter", "2025-12-01", "2026-02-28"),
+                season("winter", "2026-12-01", "2027-02-28"),
+            ]),
+            Err(SeasonError::DuplicateId(String::from("winter")))
+        );
+    }
+
+    #[test]
+    fn test_season_status_and_standings() {
+        let winter = season("winter", "2026-01-01", "2026-01-31");
+        let status = |day| season_status(&winter, NaiveDate::from_ymd_opt(2026, 1, day).unwrap());
+        assert_eq!(
+            season_status(&winter, NaiveDate::from_ymd_opt(2025, 12, 31).unwrap()),
+            SeasonStatus::Upcoming
+        );
+        assert_eq!(status(1), SeasonStatus::Current);
+        assert_eq!(status(31), SeasonStatus::Current);
+        assert_eq!(
+            season_status(&winter, NaiveDate::from_ymd_opt(2026, 2, 1).unwrap()),
+            SeasonStatus::Completed
+        );
+
+        let entry = |date: &str, username: &str, time| ResultEntry {
+            date: date.to_string(),
+            username: username.to_string(),
+            time,
+            rank: 0,
+        };
+        let results = vec![
+            entry("2025-12-31", "bob", 10),
+            entry("2025-12-31", "alice", 50),
+            entry("2026-01-05", "alice", 30),
+            entry("2026-01-05", "bob", 40),
+            entry("2026-01-06", "alice", 35),
+            entry("2026-01-06", "bob", 45),
+        ];
+
+        let standings = compute_season_standings(&winter, &results);
+        assert_eq!(standings.len(), 2);
+        assert_eq!(
+            (standings[0].rank, standings[0].username.as_str()),
+            (1, "alice")
+        );
+        assert_eq!(standings[0].num_wins, 2);
+        assert_eq!(standings[0].num_played, 2);
+        assert_eq!(standings[1].rank, 2);
+        assert!(standings
+            .iter()
+            .all(|standing| standing.season_id == "winter"));
+    }
 }